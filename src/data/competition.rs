@@ -0,0 +1,216 @@
+use std::{fmt::Display, time::Duration};
+
+use super::{Penalty, Solve, SolveTime, SolvesSeq};
+
+/// The format of a competition round.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RoundFormat {
+    #[default]
+    AverageOf5,
+    MeanOf3,
+    BestOf3,
+    BestOf1,
+}
+
+impl RoundFormat {
+    pub const ALL: [Self; 4] = [
+        Self::AverageOf5,
+        Self::MeanOf3,
+        Self::BestOf3,
+        Self::BestOf1,
+    ];
+
+    /// Get the number of attempts of a round.
+    pub fn attempts(&self) -> usize {
+        match self {
+            Self::AverageOf5 => 5,
+            Self::MeanOf3 | Self::BestOf3 => 3,
+            Self::BestOf1 => 1,
+        }
+    }
+
+    /// Get the number of attempts in which the cutoff must be met, if the format has a cutoff.
+    pub fn cutoff_attempts(&self) -> Option<usize> {
+        match self {
+            Self::AverageOf5 => Some(2),
+            Self::MeanOf3 | Self::BestOf3 => Some(1),
+            Self::BestOf1 => None,
+        }
+    }
+
+    /// Return `true` if the round is ranked by its average rather than its best single.
+    pub fn is_ranked_by_average(&self) -> bool {
+        matches!(self, Self::AverageOf5 | Self::MeanOf3)
+    }
+}
+
+impl Display for RoundFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::AverageOf5 => "Average of 5",
+                Self::MeanOf3 => "Mean of 3",
+                Self::BestOf3 => "Best of 3",
+                Self::BestOf1 => "Best of 1",
+            }
+        )
+    }
+}
+
+/// A competition round, recorded separately from sessions.
+#[derive(Debug, Clone)]
+pub struct Round {
+    pub format: RoundFormat,
+    /// A result must be under this time in the first attempts to continue the round.
+    pub cutoff: Option<Duration>,
+    /// Attempts reaching this time are DNF.
+    pub time_limit: Option<Duration>,
+    attempts: Vec<Solve>,
+}
+
+impl Round {
+    pub fn new(
+        format: RoundFormat,
+        cutoff: Option<Duration>,
+        time_limit: Option<Duration>,
+    ) -> Self {
+        Self {
+            format,
+            cutoff: cutoff.filter(|_| format.cutoff_attempts().is_some()),
+            time_limit,
+            attempts: Vec::new(),
+        }
+    }
+
+    /// Add an attempt to the round. Attempts over the time limit become DNF.
+    pub fn add_attempt(&mut self, mut solve: Solve) {
+        Self::limit(self.time_limit, &mut solve);
+        self.attempts.push(solve);
+    }
+
+    /// Change the penalty of the last attempt.
+    pub fn set_last_penalty(&mut self, penalty: Option<Penalty>) {
+        let time_limit = self.time_limit;
+        if let Some(solve) = self.attempts.last_mut() {
+            solve.time.penalty = penalty;
+            Self::limit(time_limit, solve);
+        }
+    }
+
+    pub fn attempts(&self) -> &[Solve] {
+        &self.attempts
+    }
+
+    /// Return `Some(true)` if the cutoff was met, `Some(false)` if it was missed,
+    /// and `None` if there is no cutoff or it is not decided yet.
+    pub fn made_cutoff(&self) -> Option<bool> {
+        let cutoff = self.cutoff?;
+        let n = self.format.cutoff_attempts()?;
+        let made = self
+            .attempts
+            .iter()
+            .take(n)
+            .any(|s| s.time.recorded_time().is_some_and(|time| time < cutoff));
+
+        match (made, self.attempts.len() >= n) {
+            (true, _) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => None,
+        }
+    }
+
+    /// Return `true` if all attempts were done, or the cutoff was missed.
+    pub fn is_finished(&self) -> bool {
+        self.attempts.len() >= self.format.attempts() || self.made_cutoff() == Some(false)
+    }
+
+    pub fn best(&self) -> Option<SolveTime> {
+        self.attempts.iter().map(|s| s.time).min()
+    }
+
+    /// Get the average or mean of the round, once every attempt was done.
+    pub fn average(&self) -> Option<SolveTime> {
+        if self.attempts.len() < self.format.attempts() {
+            return None;
+        }
+
+        let attempts = self.attempts.as_slice();
+        match self.format {
+            RoundFormat::AverageOf5 => attempts.average_of_n(),
+            RoundFormat::MeanOf3 => attempts.mean_of_n(),
+            RoundFormat::BestOf3 | RoundFormat::BestOf1 => None,
+        }
+    }
+
+    /// Get the result the round is ranked by: the average if the format is ranked by
+    /// average and the cutoff was met, otherwise the best single.
+    pub fn result(&self) -> Option<SolveTime> {
+        match (self.format.is_ranked_by_average(), self.average()) {
+            (true, Some(average)) => Some(average),
+            _ => self.best(),
+        }
+    }
+
+    /// Make `solve` a DNF if it reached the time limit.
+    fn limit(time_limit: Option<Duration>, solve: &mut Solve) {
+        if let (Some(time_limit), Some(time)) = (time_limit, solve.time.recorded_time()) {
+            if time >= time_limit {
+                solve.time.penalty = Some(Penalty::Dnf);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn attempt(millis: u64, penalty: Option<Penalty>) -> Solve {
+        Solve::new(
+            SolveTime::new(Duration::from_millis(millis), penalty),
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn round_with_cutoff_and_time_limit() {
+        let cutoff = Some(Duration::from_secs(120));
+        let time_limit = Some(Duration::from_secs(180));
+
+        let mut round = Round::new(RoundFormat::AverageOf5, cutoff, time_limit);
+        round.add_attempt(attempt(125_000, None));
+        assert_eq!(round.made_cutoff(), None);
+        round.add_attempt(attempt(119_000, Some(Penalty::Plus2)));
+        assert_eq!(round.made_cutoff(), Some(false));
+        assert!(round.is_finished());
+        assert_eq!(round.average(), None);
+        assert_eq!(
+            round.result(),
+            Some(SolveTime::new(
+                Duration::from_millis(119_000),
+                Some(Penalty::Plus2)
+            ))
+        );
+
+        let mut round = Round::new(RoundFormat::AverageOf5, cutoff, time_limit);
+        for millis in [130_000, 100_000, 190_000, 110_000] {
+            round.add_attempt(attempt(millis, None));
+        }
+        assert_eq!(round.made_cutoff(), Some(true));
+        assert!(round.attempts()[2].time.is_dnf());
+        assert!(!round.is_finished());
+        round.add_attempt(attempt(120_000, None));
+        assert!(round.is_finished());
+        assert_eq!(
+            round.result(),
+            Some(SolveTime::new(Duration::from_millis(120_000), None))
+        );
+
+        let mut round = Round::new(RoundFormat::BestOf1, cutoff, None);
+        assert_eq!(round.cutoff, None);
+        round.add_attempt(attempt(200_000, None));
+        assert!(round.is_finished());
+    }
+}
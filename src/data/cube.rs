@@ -0,0 +1,581 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A face of a cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Face {
+    U,
+    R,
+    F,
+    D,
+    L,
+    B,
+}
+
+impl Face {
+    pub const ALL: [Self; 6] = [Self::U, Self::R, Self::F, Self::D, Self::L, Self::B];
+
+    /// Get the axis the face lies on and whether it is on the positive side.
+    fn axis(&self) -> (Axis, bool) {
+        match self {
+            Self::R => (Axis::X, true),
+            Self::L => (Axis::X, false),
+            Self::U => (Axis::Y, true),
+            Self::D => (Axis::Y, false),
+            Self::F => (Axis::Z, true),
+            Self::B => (Axis::Z, false),
+        }
+    }
+
+    /// Get the outward normal of the face.
+    fn normal(&self) -> [i32; 3] {
+        let (axis, positive) = self.axis();
+        let mut normal = [0; 3];
+        normal[axis as usize] = if positive { 1 } else { -1 };
+        normal
+    }
+
+    /// Get the directions of increasing column and row of the stickers on the face.
+    fn sticker_axes(&self) -> ([i32; 3], [i32; 3]) {
+        match self {
+            Self::U => ([1, 0, 0], [0, 0, 1]),
+            Self::D => ([1, 0, 0], [0, 0, -1]),
+            Self::F => ([1, 0, 0], [0, -1, 0]),
+            Self::B => ([-1, 0, 0], [0, -1, 0]),
+            Self::R => ([0, 0, -1], [0, -1, 0]),
+            Self::L => ([0, 0, 1], [0, -1, 0]),
+        }
+    }
+
+    fn from_normal(normal: [i32; 3]) -> Self {
+        *Self::ALL
+            .iter()
+            .find(|face| face.normal() == normal)
+            .expect("normal must be a unit axis vector")
+    }
+
+    fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'U' => Some(Self::U),
+            'R' => Some(Self::R),
+            'F' => Some(Self::F),
+            'D' => Some(Self::D),
+            'L' => Some(Self::L),
+            'B' => Some(Self::B),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Face {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// An axis of a cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// The axis through `L` and `R`.
+    X = 0,
+    /// The axis through `D` and `U`.
+    Y = 1,
+    /// The axis through `B` and `F`.
+    Z = 2,
+}
+
+impl Axis {
+    /// Rotate a vector a quarter turn clockwise, as seen from the positive side of the axis.
+    fn rotate(&self, [x, y, z]: [i32; 3]) -> [i32; 3] {
+        match self {
+            Self::X => [x, z, -y],
+            Self::Y => [-z, y, x],
+            Self::Z => [y, -x, z],
+        }
+    }
+}
+
+/// A slice move between two opposite faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slice {
+    /// Inner layers between `L` and `R`, turning like `L`.
+    M,
+    /// Inner layers between `D` and `U`, turning like `D`.
+    E,
+    /// Inner layers between `F` and `B`, turning like `F`.
+    S,
+}
+
+impl Slice {
+    fn face(&self) -> Face {
+        match self {
+            Self::M => Face::L,
+            Self::E => Face::D,
+            Self::S => Face::F,
+        }
+    }
+}
+
+/// The layers turned by a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    /// The outer layer of a face, like `R`.
+    Face(Face),
+    /// An outer block of layers of a face, like `Rw` or `3Rw`.
+    Wide(Face, u8),
+    /// The inner layers, like `M`.
+    Slice(Slice),
+    /// The whole cube, like `x`.
+    Rotation(Axis),
+}
+
+impl Turn {
+    /// Get the axis the turn rotates around.
+    pub fn axis(&self) -> Axis {
+        match self {
+            Self::Face(face) | Self::Wide(face, _) => face.axis().0,
+            Self::Slice(slice) => slice.face().axis().0,
+            Self::Rotation(axis) => *axis,
+        }
+    }
+}
+
+/// The amount of a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Amount {
+    /// A clockwise quarter turn.
+    Single,
+    /// A half turn.
+    Double,
+    /// A counterclockwise quarter turn.
+    Inverse,
+}
+
+impl Amount {
+    pub const ALL: [Self; 3] = [Self::Single, Self::Double, Self::Inverse];
+
+    fn quarter_turns(&self) -> usize {
+        match self {
+            Self::Single => 1,
+            Self::Double => 2,
+            Self::Inverse => 3,
+        }
+    }
+
+    fn from_quarter_turns(quarter_turns: usize) -> Option<Self> {
+        match quarter_turns % 4 {
+            1 => Some(Self::Single),
+            2 => Some(Self::Double),
+            3 => Some(Self::Inverse),
+            _ => None,
+        }
+    }
+
+    fn inverse(&self) -> Self {
+        match self {
+            Self::Single => Self::Inverse,
+            Self::Double => Self::Double,
+            Self::Inverse => Self::Single,
+        }
+    }
+}
+
+/// A move on a cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub turn: Turn,
+    pub amount: Amount,
+}
+
+impl Move {
+    /// Create a new `Move`.
+    pub fn new(turn: Turn, amount: Amount) -> Self {
+        Self { turn, amount }
+    }
+
+    /// Get the inverse of the move.
+    pub fn inverse(&self) -> Self {
+        Self {
+            turn: self.turn,
+            amount: self.amount.inverse(),
+        }
+    }
+
+    /// Get the move count of the move in the outer block turn metric (OBTM).
+    ///
+    /// Rotations count as zero and slice moves as two. On a 3x3x3 cube this
+    /// is the same as the half turn metric (HTM).
+    pub fn move_count(&self) -> u32 {
+        match self.turn {
+            Turn::Face(_) | Turn::Wide(_, _) => 1,
+            Turn::Slice(_) => 2,
+            Turn::Rotation(_) => 0,
+        }
+    }
+
+    /// Get the face the move turns like, and the first and last turned layers
+    /// counted from that face starting at 1.
+    fn layers(&self, n: usize) -> (Face, usize, usize) {
+        match self.turn {
+            Turn::Face(face) => (face, 1, 1),
+            Turn::Wide(face, depth) => (face, 1, (depth as usize).min(n)),
+            Turn::Slice(slice) => (slice.face(), 2, n - 1),
+            Turn::Rotation(Axis::X) => (Face::R, 1, n),
+            Turn::Rotation(Axis::Y) => (Face::U, 1, n),
+            Turn::Rotation(Axis::Z) => (Face::F, 1, n),
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.turn {
+            Turn::Face(face) => write!(f, "{}", face)?,
+            Turn::Wide(face, 2) => write!(f, "{}w", face)?,
+            Turn::Wide(face, depth) => write!(f, "{}{}w", depth, face)?,
+            Turn::Slice(slice) => write!(f, "{:?}", slice)?,
+            Turn::Rotation(axis) => write!(f, "{}", format!("{:?}", axis).to_lowercase())?,
+        }
+        match self.amount {
+            Amount::Single => Ok(()),
+            Amount::Double => write!(f, "2"),
+            Amount::Inverse => write!(f, "'"),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoveError {
+    pub token: String,
+}
+
+impl Display for ParseMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid move \"{}\"", self.token)
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseMoveError {
+            token: s.to_string(),
+        };
+
+        let digits_len = s.chars().take_while(|c| c.is_ascii_digit()).count();
+        let depth = match digits_len {
+            0 => None,
+            _ => Some(s[..digits_len].parse::<u8>().map_err(|_| err())?),
+        };
+
+        let mut chars = s[digits_len..].chars().peekable();
+        let letter = chars.next().ok_or_else(err)?;
+        let wide = chars.next_if(|&c| c == 'w').is_some();
+
+        let turn = match (letter, wide, depth) {
+            ('x', false, None) => Turn::Rotation(Axis::X),
+            ('y', false, None) => Turn::Rotation(Axis::Y),
+            ('z', false, None) => Turn::Rotation(Axis::Z),
+            ('M', false, None) => Turn::Slice(Slice::M),
+            ('E', false, None) => Turn::Slice(Slice::E),
+            ('S', false, None) => Turn::Slice(Slice::S),
+            (c, false, None) if c.is_ascii_uppercase() => {
+                Turn::Face(Face::from_char(c).ok_or_else(err)?)
+            }
+            (c, false, None) if c.is_ascii_lowercase() => {
+                Turn::Wide(Face::from_char(c).ok_or_else(err)?, 2)
+            }
+            (c, true, depth) if c.is_ascii_uppercase() && depth.is_none_or(|d| d >= 2) => {
+                Turn::Wide(Face::from_char(c).ok_or_else(err)?, depth.unwrap_or(2))
+            }
+            _ => return Err(err()),
+        };
+
+        let amount = match chars.collect::<String>().as_str() {
+            "" => Amount::Single,
+            "2" | "2'" | "2’" => Amount::Double,
+            "'" | "’" => Amount::Inverse,
+            _ => return Err(err()),
+        };
+
+        Ok(Self { turn, amount })
+    }
+}
+
+/// A sequence of moves.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Algorithm {
+    pub moves: Vec<Move>,
+}
+
+impl Algorithm {
+    /// Create a new `Algorithm`.
+    pub fn new(moves: Vec<Move>) -> Self {
+        Self { moves }
+    }
+
+    /// Get the inverse of the algorithm.
+    pub fn inverse(&self) -> Self {
+        Self {
+            moves: self.moves.iter().rev().map(|m| m.inverse()).collect(),
+        }
+    }
+
+    /// Get the move count of the algorithm in the outer block turn metric (OBTM).
+    ///
+    /// On a 3x3x3 cube this is the same as the half turn metric (HTM).
+    pub fn move_count(&self) -> u32 {
+        self.moves.iter().map(|m| m.move_count()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Get the algorithm with consecutive moves of the same turn merged, like `U U2` into `U'`.
+    pub fn simplified(&self) -> Self {
+        let mut moves: Vec<Move> = Vec::with_capacity(self.moves.len());
+        for m in self.moves.iter() {
+            match moves.last() {
+                Some(last) if last.turn == m.turn => {
+                    let quarter_turns = last.amount.quarter_turns() + m.amount.quarter_turns();
+                    moves.pop();
+                    if let Some(amount) = Amount::from_quarter_turns(quarter_turns) {
+                        moves.push(Move::new(m.turn, amount));
+                    }
+                }
+                _ => moves.push(*m),
+            }
+        }
+        Self { moves }
+    }
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut moves = self.moves.iter();
+        if let Some(m) = moves.next() {
+            write!(f, "{}", m)?;
+        }
+        for m in moves {
+            write!(f, " {}", m)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace()
+            .map(Move::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::new)
+    }
+}
+
+/// A NxNxN cube, tracking the face color of each sticker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cube {
+    n: usize,
+    stickers: Vec<Face>,
+}
+
+impl Cube {
+    /// Create a solved cube with `n` layers.
+    pub fn new(n: usize) -> Self {
+        assert!(n >= 1, "a cube must have at least one layer");
+        Self {
+            n,
+            stickers: Face::ALL
+                .iter()
+                .flat_map(|&face| std::iter::repeat_n(face, n * n))
+                .collect(),
+        }
+    }
+
+    /// Get the number of layers of the cube.
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    /// Get the stickers of a face, in rows from top-left.
+    pub fn face(&self, face: Face) -> &[Face] {
+        let start = Face::ALL.iter().position(|&f| f == face).unwrap() * self.n * self.n;
+        &self.stickers[start..start + self.n * self.n]
+    }
+
+    /// Return `true` if each face of the cube has a single color, in any orientation.
+    pub fn is_solved(&self) -> bool {
+        self.stickers
+            .chunks(self.n * self.n)
+            .all(|face| face.iter().all(|&s| s == face[0]))
+    }
+
+    /// Return `true` if every sticker of a face has the color of that face.
+    pub fn is_face_solved(&self, face: Face) -> bool {
+        self.face(face).iter().all(|&s| s == face)
+    }
+
+    /// Return `true` if every layer but the top one is solved, in the standard orientation.
+    pub fn is_solved_below_top(&self) -> bool {
+        self.is_face_solved(Face::D)
+            && [Face::F, Face::R, Face::B, Face::L]
+                .iter()
+                .all(|&face| self.face(face)[self.n..].iter().all(|&s| s == face))
+    }
+
+    /// Apply a move to the cube.
+    pub fn apply_move(&mut self, m: &Move) {
+        let (face, first, last) = m.layers(self.n);
+        let (axis, positive) = face.axis();
+        let quarter_turns = if positive {
+            m.amount.quarter_turns()
+        } else {
+            4 - m.amount.quarter_turns()
+        };
+
+        let n = self.n as i32;
+        let layer_range = {
+            // Layer `d` from the face lies at coordinate `(n + 1 - 2d)` along the face normal.
+            let a = n + 1 - 2 * first as i32;
+            let b = n + 1 - 2 * last as i32;
+            if positive {
+                b..=a
+            } else {
+                -a..=-b
+            }
+        };
+
+        let mut stickers = self.stickers.clone();
+        for (index, &sticker) in self.stickers.iter().enumerate() {
+            let (mut normal, mut position) = self.sticker_location(index);
+            if !layer_range.contains(&position[axis as usize]) {
+                continue;
+            }
+            for _ in 0..quarter_turns {
+                normal = axis.rotate(normal);
+                position = axis.rotate(position);
+            }
+            stickers[self.sticker_index(normal, position)] = sticker;
+        }
+        self.stickers = stickers;
+    }
+
+    /// Apply each move of an algorithm to the cube.
+    pub fn apply(&mut self, alg: &Algorithm) {
+        for m in alg.moves.iter() {
+            self.apply_move(m);
+        }
+    }
+
+    /// Get the outward normal and the position of the piece holding a sticker.
+    ///
+    /// Piece positions are scaled by 2 so that they are integers on even cubes.
+    fn sticker_location(&self, index: usize) -> ([i32; 3], [i32; 3]) {
+        let n = self.n as i32;
+        let face = Face::ALL[index / (self.n * self.n)];
+        let row = ((index / self.n) % self.n) as i32;
+        let col = (index % self.n) as i32;
+
+        let normal = face.normal();
+        let (right, down) = face.sticker_axes();
+        let mut position = [0; 3];
+        for i in 0..3 {
+            position[i] = normal[i] * (n - 1)
+                + right[i] * (2 * col - (n - 1))
+                + down[i] * (2 * row - (n - 1));
+        }
+        (normal, position)
+    }
+
+    fn sticker_index(&self, normal: [i32; 3], position: [i32; 3]) -> usize {
+        let n = self.n as i32;
+        let face = Face::from_normal(normal);
+        let (right, down) = face.sticker_axes();
+        let dot = |a: [i32; 3], b: [i32; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let col = (dot(position, right) + n - 1) / 2;
+        let row = (dot(position, down) + n - 1) / 2;
+
+        Face::ALL.iter().position(|&f| f == face).unwrap() * self.n * self.n
+            + (row * n + col) as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_display_moves() {
+        let alg: Algorithm = "R U' F2 Rw 3Uw' r M2 x y' z2".parse().unwrap();
+        assert_eq!(alg.to_string(), "R U' F2 Rw 3Uw' Rw M2 x y' z2");
+        assert_eq!(alg.move_count(), 8);
+
+        assert!("R3".parse::<Move>().is_err());
+        assert!("Q".parse::<Move>().is_err());
+        assert!("1Rw".parse::<Move>().is_err());
+
+        let alg: Algorithm = "U U2 R R' F2 F2 F D".parse().unwrap();
+        assert_eq!(alg.simplified().to_string(), "U' F D");
+    }
+
+    #[test]
+    fn apply_moves() {
+        for n in 1..=7 {
+            let mut cube = Cube::new(n);
+            let sexy: Algorithm = "R U R' U'".parse().unwrap();
+            for _ in 0..6 {
+                cube.apply(&sexy);
+            }
+            assert!(cube.is_solved(), "(R U R' U')6 on {n}x{n}x{n}");
+        }
+
+        let mut cube = Cube::new(3);
+        let alg: Algorithm = "R U2 Fw' M E' S2 Lw x D' y2 B".parse().unwrap();
+        cube.apply(&alg);
+        assert!(!cube.is_solved());
+        cube.apply(&alg.inverse());
+        assert_eq!(cube, Cube::new(3));
+    }
+
+    #[test]
+    fn equivalent_moves() {
+        let apply = |alg: &str| {
+            let mut cube = Cube::new(3);
+            cube.apply(&alg.parse().unwrap());
+            cube
+        };
+
+        assert_eq!(apply("Rw"), apply("L x"));
+        assert_eq!(apply("M"), apply("R L' x'"));
+        assert_eq!(apply("E"), apply("U D' y'"));
+        assert_eq!(apply("S"), apply("F' B z"));
+
+        // The T permutation is its own inverse.
+        let t_perm = "R U R' U' R' F R2 U' R' U' R U R' F'";
+        assert!(!apply(t_perm).is_solved());
+        assert_eq!(apply(&format!("{t_perm} {t_perm}")), Cube::new(3));
+
+        // R brings the stickers of F up to U.
+        let cube = apply("R");
+        assert_eq!(
+            cube.face(Face::U),
+            &[
+                Face::U,
+                Face::U,
+                Face::F,
+                Face::U,
+                Face::U,
+                Face::F,
+                Face::U,
+                Face::U,
+                Face::F,
+            ]
+        );
+    }
+}
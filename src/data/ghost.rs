@@ -0,0 +1,179 @@
+use std::{fmt::Display, time::Duration};
+
+use super::{Session, SolveTime};
+
+/// A previous session replayed alongside the current one.
+#[derive(Clone)]
+pub struct Ghost {
+    session: Session,
+}
+
+/// Difference between a time and the time of the ghost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GhostDelta {
+    /// Faster than the ghost by the given duration.
+    Ahead(Duration),
+    /// Slower than, or as fast as the ghost by the given duration.
+    Behind(Duration),
+}
+
+/// How many of some results beat the ghost.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GhostScore {
+    pub beaten: usize,
+    /// Number of results that could be compared with the ghost.
+    pub compared: usize,
+}
+
+/// Comparison of a session with a ghost, index by index.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GhostSummary {
+    pub solves: GhostScore,
+    pub ao5: GhostScore,
+    pub ao12: GhostScore,
+}
+
+impl Ghost {
+    pub fn new(session: Session) -> Self {
+        Self { session }
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Get the time of the ghost for the solve at `index`, if it was a timed solve.
+    pub fn time(&self, index: usize) -> Option<SolveTime> {
+        self.session
+            .get_solve(index)
+            .filter(|s| s.is_timed())
+            .map(|s| s.time)
+    }
+
+    /// Compare a running time with the time of the ghost for the solve at `index`.
+    ///
+    /// Returns `None` if the ghost has no time for that solve, or if it was a DNF.
+    pub fn delta(&self, index: usize, time: Duration) -> Option<GhostDelta> {
+        let ghost_time = self.time(index)?.recorded_time()?;
+        Some(if time < ghost_time {
+            GhostDelta::Ahead(ghost_time - time)
+        } else {
+            GhostDelta::Behind(time - ghost_time)
+        })
+    }
+
+    /// Count the solves and averages of `session` that beat the ghost at the same index.
+    pub fn summary(&self, session: &Session) -> GhostSummary {
+        let mut summary = GhostSummary::default();
+        for (se, ge) in session.iter().zip(self.session.iter()) {
+            if se.solve.is_timed() && ge.solve.is_timed() {
+                summary.solves.add(se.solve.time < ge.solve.time);
+            }
+            if let (Some(ao5), Some(ghost_ao5)) = (se.ao5, ge.ao5) {
+                summary.ao5.add(ao5 < ghost_ao5);
+            }
+            if let (Some(ao12), Some(ghost_ao12)) = (se.ao12, ge.ao12) {
+                summary.ao12.add(ao12 < ghost_ao12);
+            }
+        }
+        summary
+    }
+}
+
+impl GhostScore {
+    fn add(&mut self, beaten: bool) {
+        self.compared += 1;
+        if beaten {
+            self.beaten += 1;
+        }
+    }
+}
+
+impl Display for GhostDelta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (sign, delta) = match self {
+            Self::Ahead(delta) => ('-', delta),
+            Self::Behind(delta) => ('+', delta),
+        };
+        write!(
+            f,
+            "{}{}.{:0>2}",
+            sign,
+            delta.as_secs(),
+            delta.subsec_millis() / 10
+        )
+    }
+}
+
+impl Display for GhostScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.beaten, self.compared)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data::{Penalty, Solve};
+
+    fn session(times: &[(u64, Option<Penalty>)]) -> Session {
+        let mut session = Session::new();
+        for &(millis, penalty) in times {
+            session.add_solve(Solve::new(
+                SolveTime::new(Duration::from_millis(millis), penalty),
+                String::new(),
+            ));
+        }
+        session
+    }
+
+    #[test]
+    fn compare_with_ghost() {
+        let ghost = Ghost::new(session(&[
+            (12_000, None),
+            (11_000, None),
+            (13_000, Some(Penalty::Dnf)),
+            (10_000, None),
+            (12_500, None),
+            (11_500, None),
+        ]));
+
+        assert_eq!(
+            ghost.delta(0, Duration::from_millis(11_250)),
+            Some(GhostDelta::Ahead(Duration::from_millis(750)))
+        );
+        assert_eq!(
+            ghost
+                .delta(1, Duration::from_millis(12_000))
+                .unwrap()
+                .to_string(),
+            "+1.00"
+        );
+        assert_eq!(ghost.delta(2, Duration::from_millis(12_000)), None);
+        assert_eq!(ghost.delta(6, Duration::from_millis(12_000)), None);
+
+        let summary = ghost.summary(&session(&[
+            (11_000, None),
+            (11_000, None),
+            (14_000, None),
+            (10_500, Some(Penalty::Plus2)),
+            (11_000, None),
+        ]));
+        assert_eq!(
+            summary.solves,
+            GhostScore {
+                beaten: 3,
+                compared: 5
+            }
+        );
+        // 11.50 against 11.83.
+        assert_eq!(
+            summary.ao5,
+            GhostScore {
+                beaten: 1,
+                compared: 1
+            }
+        );
+        assert_eq!(summary.ao12, GhostScore::default());
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fmt::Display, time::SystemTime};
+
+use chrono::{DateTime, Local, NaiveDate};
+
+use super::{Session, SolveTime};
+
+/// The statistic a goal is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalStatistic {
+    Single,
+    /// Average of the given number of solves.
+    Average(usize),
+    /// Mean of the session, ignoring DNFs.
+    Mean,
+}
+
+impl GoalStatistic {
+    /// Get the best value of the statistic in a session.
+    pub fn best(&self, session: &Session) -> Option<SolveTime> {
+        match self {
+            Self::Single => session
+                .iter()
+                .filter(|se| se.solve.is_timed())
+                .map(|se| se.solve.time)
+                .min(),
+            Self::Average(n) => session.best_average_of(*n),
+            Self::Mean => session.mean(),
+        }
+    }
+}
+
+impl Display for GoalStatistic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Single => write!(f, "single"),
+            Self::Average(n) => write!(f, "ao{}", n),
+            Self::Mean => write!(f, "session mean"),
+        }
+    }
+}
+
+/// State of a goal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalStatus {
+    InProgress,
+    /// The goal was reached at the given time.
+    Reached(SystemTime),
+    /// The deadline passed before the goal was reached.
+    Missed,
+}
+
+/// A target for a statistic, like sub-15 ao100 by December.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Goal {
+    pub statistic: GoalStatistic,
+    /// The statistic must be under this time.
+    pub target: SolveTime,
+    /// Last day to reach the goal, included.
+    pub deadline: Option<NaiveDate>,
+    pub status: GoalStatus,
+}
+
+impl Goal {
+    pub fn new(statistic: GoalStatistic, target: SolveTime, deadline: Option<NaiveDate>) -> Self {
+        Self {
+            statistic,
+            target,
+            deadline,
+            status: GoalStatus::InProgress,
+        }
+    }
+
+    /// Update the status of the goal from a session at time `now`.
+    ///
+    /// Returns `true` if the goal was just reached.
+    pub fn evaluate(&mut self, session: &Session, now: SystemTime) -> bool {
+        if self.status != GoalStatus::InProgress {
+            return false;
+        }
+
+        let today = DateTime::<Local>::from(now).date_naive();
+        if self.deadline.is_some_and(|deadline| today > deadline) {
+            self.status = GoalStatus::Missed;
+            false
+        } else if self.is_met_by(session) {
+            self.status = GoalStatus::Reached(now);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Return `true` if the best value of the statistic in `session` is under the target.
+    pub fn is_met_by(&self, session: &Session) -> bool {
+        self.statistic
+            .best(session)
+            .is_some_and(|best| !best.is_dnf() && best < self.target)
+    }
+
+    /// Progress toward the target, from 0.0 to 1.0, as the ratio of the target to the
+    /// best value of the statistic.
+    pub fn progress(&self, session: &Session) -> f32 {
+        if let GoalStatus::Reached(_) = self.status {
+            return 1.0;
+        }
+
+        match (
+            self.target.recorded_time(),
+            self.statistic.best(session).and_then(|b| b.recorded_time()),
+        ) {
+            (Some(target), Some(best)) if !best.is_zero() => {
+                (target.as_secs_f32() / best.as_secs_f32()).min(1.0)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+impl Display for Goal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sub-{} {}", self.target, self.statistic)?;
+        if let Some(deadline) = self.deadline {
+            write!(f, " by {}", deadline)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::data::Solve;
+
+    #[test]
+    fn evaluate_goal() {
+        let mut session = Session::new();
+        let mut goal = Goal::new(
+            GoalStatistic::Average(3),
+            "15".parse().unwrap(),
+            NaiveDate::from_ymd_opt(2100, 12, 31),
+        );
+        let now = SystemTime::now();
+
+        for time in ["16.00", "14.00", "DNF", "15.50", "13.00"] {
+            assert!(!goal.evaluate(&session, now));
+            session.add_solve(Solve::new(time.parse().unwrap(), String::new()));
+        }
+        assert_eq!(goal.status, GoalStatus::InProgress);
+        assert!((goal.progress(&session) - 15.0 / 15.5).abs() < 1e-6);
+
+        session.add_solve(Solve::new("14.50".parse().unwrap(), String::new()));
+        assert!(goal.evaluate(&session, now));
+        assert_eq!(goal.status, GoalStatus::Reached(now));
+        assert!(!goal.evaluate(&session, now));
+
+        let mut late = Goal::new(
+            GoalStatistic::Single,
+            "10".parse().unwrap(),
+            NaiveDate::from_ymd_opt(2000, 1, 1),
+        );
+        assert!(!late.evaluate(&session, now + Duration::from_secs(1)));
+        assert_eq!(late.status, GoalStatus::Missed);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Days, Local, NaiveDate};
+
+use super::{Session, Solve, SolveTime};
+
+/// Solves done on a calendar day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub n_solves: usize,
+    /// Sum and count of the timed solves which are not DNF.
+    sum: SolveTime,
+    n_timed: u32,
+}
+
+impl Day {
+    fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            n_solves: 0,
+            sum: SolveTime::default(),
+            n_timed: 0,
+        }
+    }
+
+    /// Get the mean of the timed solves of the day, ignoring DNFs.
+    pub fn mean(&self) -> Option<SolveTime> {
+        (self.n_timed > 0).then(|| self.sum / self.n_timed)
+    }
+}
+
+/// Practice history, grouping solves by local calendar day.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    days: BTreeMap<NaiveDate, Day>,
+}
+
+impl History {
+    /// Create the history of the solves of several sessions.
+    pub fn new<'a>(sessions: impl IntoIterator<Item = &'a Session>) -> Self {
+        let mut history = Self::default();
+        for session in sessions {
+            for se in session.iter() {
+                let date = DateTime::<Local>::from(se.solve.timestamp).date_naive();
+                history.add(date, &se.solve);
+            }
+        }
+        history
+    }
+
+    fn add(&mut self, date: NaiveDate, solve: &Solve) {
+        let day = self.days.entry(date).or_insert_with(|| Day::new(date));
+        day.n_solves += 1;
+        if solve.is_timed() && !solve.time.is_dnf() {
+            day.sum = day.sum + solve.time;
+            day.n_timed += 1;
+        }
+    }
+
+    /// Get the solves of a day, if any.
+    pub fn day(&self, date: NaiveDate) -> Option<&Day> {
+        self.days.get(&date)
+    }
+
+    /// Iterate over the days with solves, from the oldest.
+    pub fn days(&self) -> impl DoubleEndedIterator<Item = &Day> {
+        self.days.values()
+    }
+
+    /// Get the number of consecutive days with solves up to `today`.
+    ///
+    /// A streak is still current if there are no solves today yet but there were yesterday.
+    pub fn current_streak(&self, today: NaiveDate) -> usize {
+        let mut date = match self.days.contains_key(&today) {
+            true => today,
+            false => match today.checked_sub_days(Days::new(1)) {
+                Some(yesterday) => yesterday,
+                None => return 0,
+            },
+        };
+
+        let mut streak = 0;
+        while self.days.contains_key(&date) {
+            streak += 1;
+            match date.pred_opt() {
+                Some(pred) => date = pred,
+                None => break,
+            }
+        }
+        streak
+    }
+
+    /// Get the largest number of consecutive days with solves.
+    pub fn longest_streak(&self) -> usize {
+        let mut longest = 0;
+        let mut streak = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for &date in self.days.keys() {
+            streak = match previous {
+                Some(p) if p.succ_opt() == Some(date) => streak + 1,
+                _ => 1,
+            };
+            longest = longest.max(streak);
+            previous = Some(date);
+        }
+        longest
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn solve(time: &str) -> Solve {
+        Solve::new(time.parse().unwrap(), String::new())
+    }
+
+    #[test]
+    fn daily_history_and_streaks() {
+        let date = |d| NaiveDate::from_ymd_opt(2023, 3, d).unwrap();
+        let mut history = History::default();
+        for (d, time) in [
+            (1, "12.00"),
+            (1, "14.00"),
+            (1, "DNF"),
+            (2, "13.00"),
+            (3, "11.00"),
+            (6, "10.00"),
+            (7, "12.00"),
+        ] {
+            history.add(date(d), &solve(time));
+        }
+
+        let first = history.day(date(1)).unwrap();
+        assert_eq!(first.n_solves, 3);
+        assert_eq!(
+            first.mean(),
+            Some(SolveTime::new(Duration::from_secs(13), None))
+        );
+        assert_eq!(history.day(date(4)), None);
+
+        assert_eq!(history.longest_streak(), 3);
+        assert_eq!(history.current_streak(date(7)), 2);
+        assert_eq!(history.current_streak(date(8)), 2);
+        assert_eq!(history.current_streak(date(9)), 0);
+    }
+}
@@ -1,5 +1,27 @@
+pub use competition::{Round, RoundFormat};
+pub use cube::{Algorithm, Cube, Face, Move, ParseMoveError};
+pub use ghost::{Ghost, GhostDelta, GhostScore, GhostSummary};
+pub use goal::{Goal, GoalStatistic, GoalStatus};
+pub use history::{Day, History};
+pub use query::{ParseQueryError, PenaltyFilter, SolveQuery};
+pub use relay::{Relay, RelayLeg, RelaySet};
+pub use scramble_queue::{ImportScramblesError, ScrambleQueue};
 pub use session::Session;
-pub use solve::{Penalty, Solve, SolveTime, SolvesSeq};
+pub use solve::{
+    Bld, Fmc, MoveMean, MultiBld, MultiBldResult, ParseSolveTimeError, Penalty, SolutionError,
+    Solve, SolveTime, SolvesSeq,
+};
+pub use trainer::{Case, CaseSet, CaseStats, Trainer};
 
+mod competition;
+pub mod cube;
+mod ghost;
+mod goal;
+mod history;
+mod query;
+mod relay;
+pub mod scramble;
+mod scramble_queue;
 mod session;
 mod solve;
+mod trainer;
@@ -0,0 +1,194 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Local, NaiveDate};
+
+use super::{Solve, SolveTime};
+
+/// The penalty state a query matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PenaltyFilter {
+    /// Solves without penalty.
+    Ok,
+    Plus2,
+    Dnf,
+}
+
+/// A search over solves.
+///
+/// A query is made of space-separated terms, all of which must match:
+///
+/// - `>12.5`, `<1:05` or `time:10-15` for a time range, excluding DNFs,
+/// - `from:2023-01-31` and `to:2023-02-28` for a date range, both included,
+/// - `penalty:ok`, `penalty:+2` or `penalty:dnf`,
+/// - `tag:lockup` for a tag, quoted like `tag:"pll skip"` if it has spaces,
+/// - any other word to search in the comment, ignoring case.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SolveQuery {
+    pub min_time: Option<SolveTime>,
+    pub max_time: Option<SolveTime>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub penalty: Option<PenaltyFilter>,
+    pub tags: Vec<String>,
+    pub words: Vec<String>,
+}
+
+impl SolveQuery {
+    /// Return `true` if the query has no term, matching every solve.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Return `true` if a solve matches every term of the query.
+    pub fn matches(&self, solve: &Solve) -> bool {
+        let time = &solve.time;
+        if self.min_time.is_some() || self.max_time.is_some() {
+            if time.is_dnf() {
+                return false;
+            }
+            if self.min_time.is_some_and(|min| *time < min)
+                || self.max_time.is_some_and(|max| *time > max)
+            {
+                return false;
+            }
+        }
+
+        let date = DateTime::<Local>::from(solve.timestamp).date_naive();
+        if self.from.is_some_and(|from| date < from) || self.to.is_some_and(|to| date > to) {
+            return false;
+        }
+
+        let penalty_matches = match self.penalty {
+            None => true,
+            Some(PenaltyFilter::Ok) => time.penalty.is_none(),
+            Some(PenaltyFilter::Plus2) => time.is_plus2(),
+            Some(PenaltyFilter::Dnf) => time.is_dnf(),
+        };
+
+        let comment = solve.comment.to_lowercase();
+        penalty_matches
+            && self.tags.iter().all(|tag| solve.has_tag(tag))
+            && self
+                .words
+                .iter()
+                .all(|word| comment.contains(&word.to_lowercase()))
+    }
+}
+
+/// An error which can be returned when parsing a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseQueryError {
+    pub term: String,
+}
+
+impl Display for ParseQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid search term \"{}\"", self.term)
+    }
+}
+
+impl std::error::Error for ParseQueryError {}
+
+impl FromStr for SolveQuery {
+    type Err = ParseQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Self::default();
+        for term in split_terms(s) {
+            let err = || ParseQueryError { term: term.clone() };
+            let time = |t: &str| {
+                t.parse::<SolveTime>()
+                    .ok()
+                    .filter(|t| !t.is_dnf())
+                    .ok_or_else(err)
+            };
+            let date = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| err());
+
+            if let Some(min) = term.strip_prefix('>') {
+                query.min_time = Some(time(min)?);
+            } else if let Some(max) = term.strip_prefix('<') {
+                query.max_time = Some(time(max)?);
+            } else if let Some((key, value)) = term.split_once(':').filter(|(key, _)| {
+                ["time", "from", "to", "penalty", "tag"].contains(&key.to_lowercase().as_str())
+            }) {
+                match key.to_lowercase().as_str() {
+                    "time" => {
+                        let (min, max) = value.split_once('-').ok_or_else(err)?;
+                        query.min_time = Some(time(min)?);
+                        query.max_time = Some(time(max)?);
+                    }
+                    "from" => query.from = Some(date(value)?),
+                    "to" => query.to = Some(date(value)?),
+                    "penalty" => {
+                        query.penalty = Some(match value.to_lowercase().as_str() {
+                            "ok" | "none" => PenaltyFilter::Ok,
+                            "+2" => PenaltyFilter::Plus2,
+                            "dnf" => PenaltyFilter::Dnf,
+                            _ => return Err(err()),
+                        })
+                    }
+                    _ if value.trim().is_empty() => return Err(err()),
+                    _ => query.tags.push(value.trim().to_string()),
+                }
+            } else {
+                query.words.push(term);
+            }
+        }
+        Ok(query)
+    }
+}
+
+/// Split a query into terms at spaces outside of double quotes, removing the quotes.
+fn split_terms(s: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut term = String::new();
+    let mut quoted = false;
+    for c in s.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !term.is_empty() {
+                    terms.push(std::mem::take(&mut term));
+                }
+            }
+            c => term.push(c),
+        }
+    }
+    if !term.is_empty() {
+        terms.push(term);
+    }
+    terms
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_match_query() {
+        let query: SolveQuery = r#">10 <1:05 from:2000-01-01 tag:"pll skip" LUBE"#.parse().unwrap();
+        assert_eq!(query.min_time, "10".parse().ok());
+        assert_eq!(query.max_time, "1:05".parse().ok());
+        assert_eq!(query.from, NaiveDate::from_ymd_opt(2000, 1, 1));
+        assert_eq!(query.tags, ["pll skip"]);
+        assert_eq!(query.words, ["LUBE"]);
+
+        let mut solve = Solve {
+            comment: String::from("New lube, smooth"),
+            tags: vec![String::from("PLL skip")],
+            ..Solve::new("12.34".parse().unwrap(), String::new())
+        };
+        assert!(query.matches(&solve));
+        assert!(!"penalty:+2".parse::<SolveQuery>().unwrap().matches(&solve));
+        assert!(!"time:5-10".parse::<SolveQuery>().unwrap().matches(&solve));
+        assert!("".parse::<SolveQuery>().unwrap().is_empty());
+
+        solve.time = "DNF".parse().unwrap();
+        assert!(!query.matches(&solve));
+        assert!("penalty:DNF".parse::<SolveQuery>().unwrap().matches(&solve));
+
+        for invalid in [">abc", "time:10", "from:yesterday", "penalty:+4", "tag:"] {
+            assert!(invalid.parse::<SolveQuery>().is_err(), "{}", invalid);
+        }
+    }
+}
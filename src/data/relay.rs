@@ -0,0 +1,149 @@
+use std::{fmt::Display, time::Duration};
+
+use super::scramble;
+
+/// A relay event, where several puzzles are solved in one attempt.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RelaySet {
+    #[default]
+    TwoToFour,
+    TwoToFive,
+    TwoToSeven,
+}
+
+impl RelaySet {
+    pub const ALL: [Self; 3] = [Self::TwoToFour, Self::TwoToFive, Self::TwoToSeven];
+
+    /// Get the number of layers of each cube of the relay, in solving order.
+    pub fn puzzles(&self) -> &'static [usize] {
+        match self {
+            Self::TwoToFour => &[2, 3, 4],
+            Self::TwoToFive => &[2, 3, 4, 5],
+            Self::TwoToSeven => &[2, 3, 4, 5, 6, 7],
+        }
+    }
+
+    /// Generate a scramble for each puzzle of the relay.
+    pub fn scramble(&self) -> Vec<String> {
+        self.puzzles()
+            .iter()
+            .map(|&n| scramble::scramble_cube(n).to_string())
+            .collect()
+    }
+}
+
+impl Display for RelaySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::TwoToFour => "2-3-4 relay",
+                Self::TwoToFive => "2-5 relay",
+                Self::TwoToSeven => "2-7 relay",
+            }
+        )
+    }
+}
+
+/// A puzzle solved as part of a relay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayLeg {
+    /// Number of layers of the cube.
+    pub puzzle: usize,
+    pub scramble: String,
+    /// Time spent on this puzzle, if splits were recorded.
+    pub split: Option<Duration>,
+}
+
+impl RelayLeg {
+    /// Get the name of the puzzle, like `3x3x3`.
+    pub fn puzzle_name(&self) -> String {
+        format!("{0}x{0}x{0}", self.puzzle)
+    }
+}
+
+/// Relay metadata of a solve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relay {
+    pub set: RelaySet,
+    /// Each puzzle of the relay, in solving order.
+    pub legs: Vec<RelayLeg>,
+}
+
+impl Relay {
+    /// Create a new `Relay`.
+    ///
+    /// `marks` are the measured times at which each puzzle but the last was
+    /// finished, and `total` is the measured time of the whole relay. Splits
+    /// are only recorded if there is a mark for every puzzle but the last.
+    pub fn new(set: RelaySet, scrambles: Vec<String>, marks: &[Duration], total: Duration) -> Self {
+        let puzzles = set.puzzles();
+        let splits: Option<Vec<Duration>> = (marks.len() + 1 == puzzles.len()).then(|| {
+            let mut previous = Duration::ZERO;
+            marks
+                .iter()
+                .chain(std::iter::once(&total))
+                .map(|&mark| {
+                    let split = mark.saturating_sub(previous);
+                    previous = mark;
+                    split
+                })
+                .collect()
+        });
+
+        let legs = puzzles
+            .iter()
+            .zip(scrambles)
+            .enumerate()
+            .map(|(i, (&puzzle, scramble))| RelayLeg {
+                puzzle,
+                scramble,
+                split: splits.as_ref().map(|splits| splits[i]),
+            })
+            .collect();
+
+        Self { set, legs }
+    }
+
+    /// Get the scrambles of the relay, one line per puzzle.
+    pub fn scramble_lines(&self) -> String {
+        self.legs
+            .iter()
+            .map(|leg| format!("{}: {}", leg.puzzle_name(), leg.scramble))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn relay_splits() {
+        let scrambles = vec!["R U".to_string(), "R U F".to_string(), "Rw U".to_string()];
+        let relay = Relay::new(
+            RelaySet::TwoToFour,
+            scrambles.clone(),
+            &[Duration::from_millis(4_120), Duration::from_millis(14_620)],
+            Duration::from_millis(59_840),
+        );
+        let splits: Vec<_> = relay.legs.iter().map(|leg| leg.split).collect();
+        assert_eq!(
+            splits,
+            vec![
+                Some(Duration::from_millis(4_120)),
+                Some(Duration::from_millis(10_500)),
+                Some(Duration::from_millis(45_220)),
+            ]
+        );
+        assert_eq!(
+            relay.scramble_lines(),
+            "2x2x2: R U\n3x3x3: R U F\n4x4x4: Rw U".to_string()
+        );
+
+        let relay = Relay::new(RelaySet::TwoToFour, scrambles, &[], Duration::from_secs(60));
+        assert!(relay.legs.iter().all(|leg| leg.split.is_none()));
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt::Display;
+
+use rand::{seq::SliceRandom, Rng};
+
+use super::cube::{Algorithm, Amount, Face, Move, Turn};
+use super::CaseSet;
+
+/// The kind of scramble generated for a 3x3x3 session.
+///
+/// Subset scrambles leave part of the cube solved to practice the remaining steps.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScrambleType {
+    /// Random moves on the whole cube.
+    #[default]
+    Standard,
+    /// The cross is solved.
+    Cross,
+    /// The cross and the front-right pair are solved.
+    XCross,
+    /// The first two layers are solved but the front-right pair.
+    LastSlot,
+    /// The first two layers are solved.
+    LastLayer,
+    /// Random `R` and `U` moves.
+    TwoGen,
+    /// The corners are solved, for blindfolded edges practice.
+    EdgesOnly,
+    /// The edges are solved, for blindfolded corners practice.
+    CornersOnly,
+}
+
+impl ScrambleType {
+    pub const ALL: [Self; 8] = [
+        Self::Standard,
+        Self::Cross,
+        Self::XCross,
+        Self::LastSlot,
+        Self::LastLayer,
+        Self::TwoGen,
+        Self::EdgesOnly,
+        Self::CornersOnly,
+    ];
+
+    /// Generate a scramble of this type for a 3x3x3 cube.
+    pub fn scramble<R: Rng + ?Sized>(&self, rng: &mut R) -> Algorithm {
+        let solution = match self {
+            Self::Standard => return random_moves(rng, 3, scramble_length(3)),
+            Self::TwoGen => {
+                return random_moves_with(
+                    rng,
+                    &[Turn::Face(Face::R), Turn::Face(Face::U)],
+                    scramble_length(3),
+                )
+            }
+            Self::Cross => slot_triggers(rng, &SLOTS, 12),
+            Self::XCross => slot_triggers(rng, &SLOTS[1..], 10),
+            Self::LastSlot => {
+                let mut solution = slot_triggers(rng, &SLOTS[..1], 3);
+                solution.extend(last_layer(rng));
+                solution
+            }
+            Self::LastLayer => last_layer(rng),
+            Self::EdgesOnly => conjugated_cycles(rng, "M' U2 M U2", 8),
+            Self::CornersOnly => conjugated_cycles(rng, "R U R' D R U' R' D'", 6),
+        };
+
+        Algorithm::new(solution).inverse().simplified()
+    }
+}
+
+impl Display for ScrambleType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Standard => "Standard scramble",
+                Self::Cross => "Cross solved",
+                Self::XCross => "XCross solved",
+                Self::LastSlot => "Last slot + last layer",
+                Self::LastLayer => "Last layer",
+                Self::TwoGen => "2-gen <R,U>",
+                Self::EdgesOnly => "Edges only",
+                Self::CornersOnly => "Corners only",
+            }
+        )
+    }
+}
+
+/// Moves taking the pair of a first-two-layers slot to the upper layer, with the
+/// face which must be turned back to insert it. The front-right slot comes first.
+const SLOTS: [[(Face, Amount); 2]; 4] = [
+    [(Face::R, Amount::Single), (Face::F, Amount::Inverse)],
+    [(Face::L, Amount::Inverse), (Face::F, Amount::Single)],
+    [(Face::R, Amount::Inverse), (Face::B, Amount::Single)],
+    [(Face::L, Amount::Single), (Face::B, Amount::Inverse)],
+];
+
+/// Generate random triggers like `R U R'` on the given slots, which scramble the
+/// pairs of the slots and the last layer but keep the rest of the cube solved.
+fn slot_triggers<R: Rng + ?Sized>(
+    rng: &mut R,
+    slots: &[[(Face, Amount); 2]],
+    count: usize,
+) -> Vec<Move> {
+    let mut moves = Vec::new();
+    for _ in 0..count {
+        let &(face, amount) = slots.choose(rng).unwrap().choose(rng).unwrap();
+        let m = Move::new(Turn::Face(face), amount);
+        moves.push(m);
+        moves.push(random_u(rng));
+        moves.push(m.inverse());
+        moves.push(random_u(rng));
+    }
+    moves
+}
+
+/// Generate a random solution of the last layer, made of random OLL and PLL cases.
+fn last_layer<R: Rng + ?Sized>(rng: &mut R) -> Vec<Move> {
+    let mut moves = Vec::new();
+    for set in [CaseSet::Oll, CaseSet::Pll] {
+        let case = set.cases().choose(rng).unwrap();
+        moves.extend(set.scramble(rng, case).inverse().moves);
+    }
+    moves
+}
+
+/// Generate conjugates of a 3-cycle by random setup moves, which keep the pieces
+/// the cycle does not affect solved.
+fn conjugated_cycles<R: Rng + ?Sized>(rng: &mut R, cycle: &str, count: usize) -> Vec<Move> {
+    let cycle: Algorithm = cycle.parse().expect("the cycle is valid");
+    let mut moves = Vec::new();
+    for _ in 0..count {
+        let length = rng.gen_range(1..=3);
+        let setup = random_moves(rng, 3, length);
+        moves.extend(setup.moves.iter().copied());
+        moves.extend(cycle.moves.iter().copied());
+        moves.extend(setup.inverse().moves);
+    }
+    moves
+}
+
+/// Get a random turn of the upper face.
+fn random_u<R: Rng + ?Sized>(rng: &mut R) -> Move {
+    Move::new(Turn::Face(Face::U), *Amount::ALL.choose(rng).unwrap())
+}
+
+/// Get the length of a random-move scramble for a NxNxN cube.
+pub fn scramble_length(n: usize) -> usize {
+    match n {
+        0..=1 => 0,
+        2 => 11,
+        3 => 25,
+        n => 20 * (n - 2),
+    }
+}
+
+/// Generate a random-move scramble of the standard length for a NxNxN cube.
+pub fn scramble_cube(n: usize) -> Algorithm {
+    random_moves(&mut rand::thread_rng(), n, scramble_length(n))
+}
+
+/// Generate a scramble of `length` random moves for a NxNxN cube.
+///
+/// Moves are never redundant: a turn is not repeated until a turn on another
+/// axis has been made, since turns on the same axis commute.
+pub fn random_moves<R: Rng + ?Sized>(rng: &mut R, n: usize, length: usize) -> Algorithm {
+    random_moves_with(rng, &scramble_turns(n), length)
+}
+
+/// Generate a scramble of `length` random moves among `turns`, never redundant.
+fn random_moves_with<R: Rng + ?Sized>(rng: &mut R, turns: &[Turn], length: usize) -> Algorithm {
+    let mut moves: Vec<Move> = Vec::with_capacity(length);
+    let mut same_axis: Vec<Turn> = Vec::new();
+
+    while moves.len() < length {
+        let Some(&turn) = turns.choose(rng) else {
+            break;
+        };
+
+        if same_axis.first().map(|t| t.axis()) != Some(turn.axis()) {
+            same_axis.clear();
+        } else if same_axis.contains(&turn) {
+            continue;
+        }
+
+        same_axis.push(turn);
+        moves.push(Move::new(turn, *Amount::ALL.choose(rng).unwrap()));
+    }
+
+    Algorithm::new(moves)
+}
+
+/// Get the turns used to scramble a NxNxN cube.
+fn scramble_turns(n: usize) -> Vec<Turn> {
+    if n == 2 {
+        // Fix a corner of the 2x2x2 cube, since it has no centers.
+        return vec![
+            Turn::Face(Face::R),
+            Turn::Face(Face::U),
+            Turn::Face(Face::F),
+        ];
+    }
+
+    let mut turns: Vec<Turn> = Face::ALL.iter().map(|&face| Turn::Face(face)).collect();
+    for depth in 2..=(n / 2) as u8 {
+        turns.extend(Face::ALL.iter().map(|&face| Turn::Wide(face, depth)));
+    }
+    turns
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data::Cube;
+
+    #[test]
+    fn random_moves_are_not_redundant() {
+        let mut rng = rand::thread_rng();
+        for n in 2..=7 {
+            let scramble = random_moves(&mut rng, n, scramble_length(n));
+            assert_eq!(scramble.moves.len(), scramble_length(n));
+            for pair in scramble.moves.windows(2) {
+                assert_ne!(pair[0].turn, pair[1].turn, "{scramble}");
+            }
+        }
+    }
+
+    #[test]
+    fn subset_scrambles() {
+        // Return `true` if the stickers at `indices` of each face have the color of the face.
+        fn solved_at(cube: &Cube, faces: &[Face], indices: &[usize]) -> bool {
+            faces
+                .iter()
+                .all(|&face| indices.iter().all(|&i| cube.face(face)[i] == face))
+        }
+        let sides = [Face::F, Face::R, Face::B, Face::L];
+
+        let mut rng = rand::thread_rng();
+        for scramble_type in ScrambleType::ALL {
+            let scramble = scramble_type.scramble(&mut rng);
+            let mut cube = Cube::new(3);
+            cube.apply(&scramble);
+            assert!(!cube.is_solved(), "{scramble_type}: {scramble}");
+
+            let cross =
+                solved_at(&cube, &[Face::D], &[1, 3, 5, 7]) && solved_at(&cube, &sides, &[7]);
+            let front_right =
+                solved_at(&cube, &[Face::F], &[5, 8]) && solved_at(&cube, &[Face::R], &[3, 6]);
+            match scramble_type {
+                ScrambleType::Standard => (),
+                ScrambleType::Cross => assert!(cross, "{scramble}"),
+                ScrambleType::XCross => assert!(cross && front_right, "{scramble}"),
+                ScrambleType::LastSlot => {
+                    assert!(cross && solved_at(&cube, &[Face::B, Face::L], &[3, 5, 6, 8]))
+                }
+                ScrambleType::LastLayer => assert!(cube.is_solved_below_top(), "{scramble}"),
+                ScrambleType::TwoGen => assert!(scramble
+                    .moves
+                    .iter()
+                    .all(|m| matches!(m.turn, Turn::Face(Face::R | Face::U)))),
+                ScrambleType::EdgesOnly => {
+                    assert!(solved_at(&cube, &Face::ALL, &[0, 2, 6, 8]), "{scramble}")
+                }
+                ScrambleType::CornersOnly => {
+                    assert!(solved_at(&cube, &Face::ALL, &[1, 3, 5, 7]), "{scramble}")
+                }
+            }
+        }
+    }
+}
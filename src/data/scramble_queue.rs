@@ -0,0 +1,398 @@
+use std::{fmt::Display, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::cube::{Algorithm, ParseMoveError};
+
+/// An error which can be returned when importing a list of scrambles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportScramblesError {
+    /// The file looks like JSON but is not valid.
+    Json(String),
+    /// A scramble contains an invalid move. Scrambles are numbered from 1.
+    InvalidScramble {
+        number: usize,
+        error: ParseMoveError,
+    },
+    /// The file contains no scramble.
+    Empty,
+}
+
+impl Display for ImportScramblesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid JSON: {}", e),
+            Self::InvalidScramble { number, error } => write!(f, "scramble {}: {}", number, error),
+            Self::Empty => write!(f, "no scrambles found"),
+        }
+    }
+}
+
+impl std::error::Error for ImportScramblesError {}
+
+/// Imported scrambles, used in order before generated ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScrambleQueue {
+    /// Name of the imported list, usually its file name.
+    pub name: String,
+    scrambles: Vec<String>,
+    /// Number of scrambles taken from the queue.
+    position: usize,
+    /// Extra scrambles of official sets, which replace the scramble of a botched attempt.
+    #[serde(default)]
+    extra_scrambles: Vec<String>,
+    /// Number of extra scrambles taken.
+    #[serde(default)]
+    extra_position: usize,
+    /// Whether the current scramble is an extra one.
+    #[serde(default)]
+    replaced: bool,
+}
+
+impl ScrambleQueue {
+    /// Parse a list of scrambles.
+    ///
+    /// The list is either a JSON array of scrambles, a JSON object with a `scrambles`
+    /// array or with `sets` of `scrambles`, an official scramble set as the TNoodle
+    /// JSON `sheets` or the WCIF `scrambleSets` of the rounds of each event, or a text
+    /// file with one scramble per line. In text files, blank lines and lines
+    /// starting with `#` are skipped, as well as numbering like `1.` or `1)`.
+    ///
+    /// Only the 3x3x3 event of official scramble sets is imported, and their extra
+    /// scrambles are kept apart as replacements.
+    pub fn parse(name: &str, text: &str) -> Result<Self, ImportScramblesError> {
+        let text = text.trim();
+        let mut extra_scrambles = Vec::new();
+        let scrambles = if text.starts_with('[') || text.starts_with('{') {
+            let value: serde_json::Value = serde_json::from_str(text)
+                .map_err(|e| ImportScramblesError::Json(e.to_string()))?;
+            let mut scrambles = Vec::new();
+            json_scrambles(&value, &mut scrambles);
+            json_extra_scrambles(&value, &mut extra_scrambles);
+            scrambles
+        } else {
+            text.lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| {
+                    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+                    match line[digits..].strip_prefix(['.', ')']) {
+                        Some(rest) if digits > 0 => rest.trim().to_string(),
+                        _ => line.to_string(),
+                    }
+                })
+                .collect()
+        };
+
+        if scrambles.is_empty() {
+            return Err(ImportScramblesError::Empty);
+        }
+        for (i, scramble) in scrambles.iter().chain(&extra_scrambles).enumerate() {
+            scramble.parse::<Algorithm>().map_err(|error| {
+                ImportScramblesError::InvalidScramble {
+                    number: i + 1,
+                    error,
+                }
+            })?;
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            scrambles,
+            position: 0,
+            extra_scrambles,
+            extra_position: 0,
+            replaced: false,
+        })
+    }
+
+    /// Take the next scramble of the queue, if any.
+    pub fn next_scramble(&mut self) -> Option<Algorithm> {
+        let scramble = self.scrambles.get(self.position)?.parse().ok()?;
+        self.position += 1;
+        self.replaced = false;
+        Some(scramble)
+    }
+
+    /// Take the next extra scramble, to replace the current scramble.
+    pub fn extra_scramble(&mut self) -> Option<Algorithm> {
+        let scramble = self
+            .extra_scrambles
+            .get(self.extra_position)?
+            .parse()
+            .ok()?;
+        self.extra_position += 1;
+        self.replaced = true;
+        Some(scramble)
+    }
+
+    /// Get the scramble last taken from the queue, if any.
+    pub fn current(&self) -> Option<Algorithm> {
+        let scramble = if self.replaced {
+            self.extra_scrambles
+                .get(self.extra_position.checked_sub(1)?)?
+        } else {
+            self.scrambles.get(self.position.checked_sub(1)?)?
+        };
+        scramble.parse().ok()
+    }
+
+    /// Get the number of scrambles taken from the queue.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.scrambles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scrambles.is_empty()
+    }
+
+    /// Get the number of extra scrambles not taken yet.
+    pub fn extra_left(&self) -> usize {
+        self.extra_scrambles.len() - self.extra_position
+    }
+
+    /// Get the path of the file the queue is saved to.
+    fn path() -> Option<PathBuf> {
+        Some(
+            dirs::data_dir()?
+                .join("kubia-timer")
+                .join("scramble-queue.json"),
+        )
+    }
+
+    /// Load the saved queue, if any.
+    pub fn load() -> Option<Self> {
+        let text = std::fs::read_to_string(Self::path()?).ok()?;
+        match serde_json::from_str(&text) {
+            Ok(queue) => Some(queue),
+            Err(e) => {
+                log::warn!("cannot read the saved scramble queue: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Save the queue with its position, to continue after a restart.
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::path().ok_or(std::io::ErrorKind::NotFound)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+    }
+
+    /// Remove the saved queue.
+    pub fn remove_saved() -> std::io::Result<()> {
+        match Self::path() {
+            Some(path) if path.exists() => std::fs::remove_file(path),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Display for ScrambleQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}/{}", self.name, self.position, self.len())
+    }
+}
+
+/// Keys of JSON objects leading to sets of scrambles, in the order they are taken.
+const JSON_SET_KEYS: [&str; 5] = ["wcif", "events", "rounds", "scrambleSets", "sheets"];
+
+/// Event of official scramble sets which is imported.
+const EVENT: &str = "333";
+
+/// Check whether an item of a JSON key is for another event than 3x3x3, as TNoodle
+/// `sheets` have an `event` and WCIF `events` have an `id`.
+fn is_other_event(key: &str, value: &serde_json::Value) -> bool {
+    let event = match key {
+        "sheets" => value.get("event"),
+        "events" => value.get("id"),
+        _ => None,
+    };
+    matches!(event, Some(serde_json::Value::String(event)) if event != EVENT)
+}
+
+/// Collect the sets of scrambles of a JSON list of scrambles.
+///
+/// A list with no sets, like an array, is a set itself.
+fn json_sets<'a>(value: &'a serde_json::Value, sets: &mut Vec<&'a serde_json::Value>) {
+    match value {
+        serde_json::Value::Array(values) if values.iter().all(|v| v.is_object()) => {
+            for value in values {
+                json_sets(value, sets);
+            }
+        }
+        serde_json::Value::Object(object)
+            if JSON_SET_KEYS.iter().any(|key| object.contains_key(*key)) =>
+        {
+            for (key, value) in JSON_SET_KEYS
+                .iter()
+                .filter_map(|key| Some((key, object.get(*key)?)))
+            {
+                match value {
+                    serde_json::Value::Array(values) => {
+                        for value in values.iter().filter(|v| !is_other_event(key, v)) {
+                            json_sets(value, sets);
+                        }
+                    }
+                    value => json_sets(value, sets),
+                }
+            }
+        }
+        value => sets.push(value),
+    }
+}
+
+/// Collect the scrambles of a JSON value.
+///
+/// A string with several lines, like a multi-blind attempt, holds one scramble per line.
+fn json_strings(value: &serde_json::Value, scrambles: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(scramble) => scrambles.extend(
+            scramble
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty())
+                .map(String::from),
+        ),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                json_strings(value, scrambles);
+            }
+        }
+        serde_json::Value::Object(object) => {
+            for value in ["sets", "scrambles"]
+                .iter()
+                .filter_map(|key| object.get(*key))
+            {
+                json_strings(value, scrambles);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect the scrambles of a JSON list of scrambles, without the extra scrambles.
+fn json_scrambles(value: &serde_json::Value, scrambles: &mut Vec<String>) {
+    let mut sets = Vec::new();
+    json_sets(value, &mut sets);
+    for set in sets {
+        json_strings(set, scrambles);
+    }
+}
+
+/// Collect the extra scrambles of the sets of a JSON list of scrambles.
+fn json_extra_scrambles(value: &serde_json::Value, scrambles: &mut Vec<String>) {
+    let mut sets = Vec::new();
+    json_sets(value, &mut sets);
+    for extra in sets.iter().filter_map(|set| set.get("extraScrambles")) {
+        json_strings(extra, scrambles);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_scramble_lists() {
+        let text = "# Round 1\n1. R U R' U'\n2) F2 D\n\nL B'\n";
+        let mut queue = ScrambleQueue::parse("round1.txt", text).unwrap();
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.current(), None);
+        assert_eq!(queue.next_scramble().unwrap().to_string(), "R U R' U'");
+        assert_eq!(queue.next_scramble().unwrap().to_string(), "F2 D");
+        assert_eq!(queue.current().unwrap().to_string(), "F2 D");
+        assert_eq!(queue.to_string(), "round1.txt 2/3");
+        assert!(queue.next_scramble().is_some());
+        assert_eq!(queue.next_scramble(), None);
+        assert_eq!(queue.position(), 3);
+
+        let json = r#"{"sets": [{"scrambles": ["R U", "F"]}, {"scrambles": ["D2"]}]}"#;
+        assert_eq!(ScrambleQueue::parse("set.json", json).unwrap().len(), 3);
+        let tnoodle = r#"{"competitionName": "Open", "sheets": [
+            {"event": "333", "round": 1, "group": "A", "scrambles": ["R U", "F"], "extraScrambles": ["B"]},
+            {"event": "333mbf", "round": 1, "group": "A", "scrambles": ["D2\nL2"], "extraScrambles": []}
+        ]}"#;
+        let mut queue = ScrambleQueue::parse("open.json", tnoodle).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.extra_left(), 1);
+        assert_eq!(queue.next_scramble().unwrap().to_string(), "R U");
+        let wcif = r#"{"wcif": {"id": "Open2026", "events": [{"id": "333", "rounds": [
+            {"id": "333-r1", "scrambleSetCount": 2, "scrambleSets": [
+                {"id": 1, "scrambles": ["R", "U"], "extraScrambles": ["F"]},
+                {"id": 2, "scrambles": ["L"], "extraScrambles": []}
+            ]}
+        ]}]}}"#;
+        let mut queue = ScrambleQueue::parse("open.json", wcif).unwrap();
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.next_scramble().unwrap().to_string(), "R");
+        assert_eq!(
+            ScrambleQueue::parse("list.json", r#"["R", "U2"]"#)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert!(matches!(
+            ScrambleQueue::parse("bad.json", "[\"R\""),
+            Err(ImportScramblesError::Json(_))
+        ));
+        assert!(matches!(
+            ScrambleQueue::parse("bad.txt", "R U\nR Q"),
+            Err(ImportScramblesError::InvalidScramble { number: 2, .. })
+        ));
+        assert_eq!(
+            ScrambleQueue::parse("empty.txt", "# nothing\n"),
+            Err(ImportScramblesError::Empty)
+        );
+    }
+
+    #[test]
+    fn import_3x3x3_of_mixed_events() {
+        let tnoodle = r#"{"sheets": [
+            {"event": "222", "round": 1, "scrambles": ["R U2"], "extraScrambles": ["F2"]},
+            {"event": "333", "round": 1, "scrambles": ["R U", "F"], "extraScrambles": ["B", "D"]},
+            {"event": "333oh", "round": 1, "scrambles": ["L"], "extraScrambles": []},
+            {"event": "333", "round": 2, "scrambles": ["U'"], "extraScrambles": ["L2"]}
+        ]}"#;
+        let mut queue = ScrambleQueue::parse("mixed.json", tnoodle).unwrap();
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.extra_left(), 3);
+        assert_eq!(queue.next_scramble().unwrap().to_string(), "R U");
+        assert_eq!(queue.extra_scramble().unwrap().to_string(), "B");
+        assert_eq!(queue.current().unwrap().to_string(), "B");
+        assert_eq!(queue.to_string(), "mixed.json 1/3");
+        assert_eq!(queue.next_scramble().unwrap().to_string(), "F");
+        assert_eq!(queue.current().unwrap().to_string(), "F");
+        assert_eq!(queue.next_scramble().unwrap().to_string(), "U'");
+        assert_eq!(queue.next_scramble(), None);
+        assert_eq!(queue.extra_left(), 2);
+
+        let wcif = r#"{"id": "Open2026", "events": [
+            {"id": "444", "rounds": [{"id": "444-r1", "scrambleSets": [
+                {"id": 1, "scrambles": ["Rw U"], "extraScrambles": ["Fw"]}
+            ]}]},
+            {"id": "333", "rounds": [{"id": "333-r1", "scrambleSets": [
+                {"id": 2, "scrambles": ["R", "U"], "extraScrambles": ["F"]}
+            ]}]}
+        ]}"#;
+        let mut queue = ScrambleQueue::parse("open.json", wcif).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.extra_left(), 1);
+        assert_eq!(queue.next_scramble().unwrap().to_string(), "R");
+        assert_eq!(queue.extra_scramble().unwrap().to_string(), "F");
+        assert_eq!(queue.extra_scramble(), None);
+
+        let other_events = r#"{"sheets": [{"event": "444", "scrambles": ["Rw U"]}]}"#;
+        assert_eq!(
+            ScrambleQueue::parse("444.json", other_events),
+            Err(ImportScramblesError::Empty)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{iter, slice};
+use std::{cell::RefCell, slice};
 
 use super::*;
 
@@ -8,12 +8,14 @@ pub struct SessionEntry {
     pub mo3: Option<SolveTime>,
     pub ao5: Option<SolveTime>,
     pub ao12: Option<SolveTime>,
+    /// Mean of 3 in moves, for fewest moves solves.
+    pub mo3_moves: Option<MoveMean>,
 }
 
 impl SolvesSeq for &[SessionEntry] {
     fn mean_of_n(&self) -> Option<SolveTime> {
         let len = self.len() as u32;
-        if len == 0 {
+        if len == 0 || self.iter().any(|se| !se.solve.is_timed()) {
             return None;
         }
 
@@ -22,50 +24,112 @@ impl SolvesSeq for &[SessionEntry] {
     }
 
     fn average_of_n(&self) -> Option<SolveTime> {
-        let len = self.len() as u32;
-        if len < 3 {
+        if self.iter().any(|se| !se.solve.is_timed()) {
             return None;
         }
 
-        let it = self.iter().map(|se| se.solve.time).enumerate();
+        solve::trimmed_average(self.iter().map(|se| se.solve.time))
+    }
 
-        let (imax, _max) = it.clone().max_by_key(|&(_, st)| st)?;
-        let (imin, _min) = it.clone().min_by_key(|&(_, st)| st)?;
-        let sum = it.fold(SolveTime::default(), |acc, (i, st)| {
-            if i != imax && i != imin {
-                acc + st
-            } else {
-                acc
-            }
-        });
-        Some(sum / (len - 2))
+    fn mean_of_moves(&self) -> Option<MoveMean> {
+        solve::mean_of_moves(self.iter().map(|se| &se.solve))
     }
 }
 
 #[derive(Clone)]
 pub struct Session {
     entries: Vec<SessionEntry>,
+    /// The kind of scramble generated for the solves of the session.
+    pub scramble_type: scramble::ScrambleType,
+    /// Best averages already computed by `best_average_of`, by number of solves.
+    best_averages: RefCell<Vec<(usize, Option<SolveTime>)>>,
 }
 
 impl Session {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            scramble_type: scramble::ScrambleType::default(),
+            best_averages: RefCell::new(Vec::new()),
         }
     }
 
     pub fn add_solve(&mut self, solve: Solve) {
+        self.best_averages.get_mut().clear();
         self.entries.push(SessionEntry {
             solve,
             mo3: None,
             ao5: None,
             ao12: None,
+            mo3_moves: None,
         });
 
         self.update_statistics_last();
     }
 
-    pub fn iter(&self) -> slice::Iter<SessionEntry> {
+    /// Create a session of the solves matching a predicate, with the statistics
+    /// computed over those solves only.
+    ///
+    /// The indices of the kept solves in this session are returned with it.
+    pub fn filtered(&self, mut predicate: impl FnMut(&Solve) -> bool) -> (Self, Vec<usize>) {
+        let mut session = Self::new();
+        session.scramble_type = self.scramble_type;
+        let mut indices = Vec::new();
+        for (i, se) in self.entries.iter().enumerate() {
+            if predicate(&se.solve) {
+                session.add_solve(se.solve.clone());
+                indices.push(i);
+            }
+        }
+        (session, indices)
+    }
+
+    /// Get the tags of the solves of the session, sorted and without duplicates.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.entries.iter().flat_map(|se| se.solve.tags.iter()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags
+    }
+
+    pub fn get_solve_mut(&mut self, index: usize) -> Option<&mut Solve> {
+        self.best_averages.get_mut().clear();
+        self.entries.get_mut(index).map(|se| &mut se.solve)
+    }
+
+    /// Insert a solve at `index`, like a removed solve put back, updating the statistics
+    /// of the solves after it.
+    pub fn insert_solve(&mut self, index: usize, solve: Solve) {
+        let index = index.min(self.entries.len());
+        self.entries.insert(
+            index,
+            SessionEntry {
+                solve,
+                mo3: None,
+                ao5: None,
+                ao12: None,
+                mo3_moves: None,
+            },
+        );
+        self.update_statistics(index);
+    }
+
+    /// Remove the solve at `index`, updating the statistics of the solves after it.
+    pub fn remove_solve(&mut self, index: usize) -> Option<Solve> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        self.best_averages.get_mut().clear();
+        let entry = self.entries.remove(index);
+        self.update_statistics(index);
+        Some(entry.solve)
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, SessionEntry> {
         self.entries.iter()
     }
 
@@ -117,11 +181,58 @@ impl Session {
             .and_then(|st| st)
     }
 
+    /// Get the best average of `n` consecutive solves, for any `n` of at least 3.
+    ///
+    /// The result is kept until the solves of the session change.
+    pub fn best_average_of(&self, n: usize) -> Option<SolveTime> {
+        if n < 3 {
+            return None;
+        }
+        if let Some(&(_, best)) = self.best_averages.borrow().iter().find(|(m, _)| *m == n) {
+            return best;
+        }
+
+        let best = match n {
+            5 => self.best_ao5(),
+            12 => self.best_ao12(),
+            _ => self
+                .entries
+                .windows(n)
+                .filter_map(|w| w.average_of_n())
+                .min(),
+        };
+        self.best_averages.borrow_mut().push((n, best));
+        best
+    }
+
+    /// Get the mean of the timed solves of the session, ignoring DNFs.
+    pub fn mean(&self) -> Option<SolveTime> {
+        let times: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|se| se.solve.is_timed() && !se.solve.time.is_dnf())
+            .map(|se| se.solve.time)
+            .collect();
+        match times.len() {
+            0 => None,
+            len => Some(times.into_iter().sum::<SolveTime>() / len as u32),
+        }
+    }
+
+    /// Get the best multi-blind result, ranked by WCA rules.
+    pub fn best_multi_bld(&self) -> Option<MultiBldResult> {
+        self.entries
+            .iter()
+            .filter_map(|se| se.solve.multi_bld())
+            .min()
+    }
+
     pub fn last_solve(&self) -> Option<&Solve> {
         self.entries.last().map(|se| &se.solve)
     }
 
     pub fn last_solve_mut(&mut self) -> Option<&mut Solve> {
+        self.best_averages.get_mut().clear();
         self.entries.last_mut().map(|se| &mut se.solve)
     }
 
@@ -137,10 +248,25 @@ impl Session {
         self.entries.last().and_then(|se| se.ao12)
     }
 
+    /// Get the average of the last `n` solves, for any `n` of at least 3.
+    pub fn last_average_of(&self, n: usize) -> Option<SolveTime> {
+        match n {
+            5 => self.last_ao5(),
+            12 => self.last_ao12(),
+            _ if n < 3 || n > self.entries.len() => None,
+            _ => (&self.entries[self.entries.len() - n..]).average_of_n(),
+        }
+    }
+
+    pub fn last_mo3_moves(&self) -> Option<MoveMean> {
+        self.entries.last().and_then(|se| se.mo3_moves)
+    }
+
     fn compute_mo3(&mut self, index: usize) -> Option<SolveTime> {
         if self.entries.len() >= 3 {
-            self.entries
-                .get(index - 2..index + 1)
+            index
+                .checked_sub(2)
+                .and_then(|start| self.entries.get(start..index + 1))
                 .and_then(|solves| solves.mean_of_n())
         } else {
             None
@@ -149,8 +275,9 @@ impl Session {
 
     fn compute_ao5(&mut self, index: usize) -> Option<SolveTime> {
         if self.entries.len() >= 5 {
-            self.entries
-                .get(index - 4..index + 1)
+            index
+                .checked_sub(4)
+                .and_then(|start| self.entries.get(start..index + 1))
                 .and_then(|solves| solves.average_of_n())
         } else {
             None
@@ -159,8 +286,9 @@ impl Session {
 
     fn compute_ao12(&mut self, index: usize) -> Option<SolveTime> {
         if self.entries.len() >= 12 {
-            self.entries
-                .get(index - 11..index + 1)
+            index
+                .checked_sub(11)
+                .and_then(|start| self.entries.get(start..index + 1))
                 .and_then(|solves| solves.average_of_n())
         } else {
             None
@@ -168,6 +296,7 @@ impl Session {
     }
 
     pub fn update_statistics(&mut self, index: usize) {
+        self.best_averages.get_mut().clear();
         let len = self.entries.len();
         for i in index..len.min(index + 3) {
             self.update_mo3(i);
@@ -189,8 +318,13 @@ impl Session {
 
     fn update_mo3(&mut self, index: usize) {
         let mo3 = self.compute_mo3(index);
+        let mo3_moves = index
+            .checked_sub(2)
+            .and_then(|start| self.entries.get(start..index + 1))
+            .and_then(|solves| solves.mean_of_moves());
         if let Some(entry) = self.entries.get_mut(index) {
             entry.mo3 = mo3;
+            entry.mo3_moves = mo3_moves;
         }
     }
 
@@ -214,3 +348,76 @@ impl Default for Session {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn filter_by_tag() {
+        let mut session = Session::new();
+        for (time, tag) in [
+            ("10.00", "lockup"),
+            ("12.00", ""),
+            ("11.00", "Lockup"),
+            ("13.00", "pll skip"),
+            ("14.00", "lockup"),
+        ] {
+            let mut solve = Solve::new(time.parse().unwrap(), String::new());
+            assert_eq!(solve.add_tag(tag), !tag.is_empty());
+            assert!(!solve.add_tag(&format!(" {} ", tag.to_uppercase())));
+            session.add_solve(solve);
+        }
+        assert_eq!(session.tags(), ["lockup", "pll skip"]);
+
+        let (filtered, indices) = session.filtered(|s| s.has_tag("lockup"));
+        assert_eq!(indices, [0, 2, 4]);
+        assert_eq!(
+            filtered.last_mo3().map(|t| t.to_string()),
+            Some("11.66".to_string())
+        );
+        assert_eq!(filtered.last_ao5(), None);
+
+        assert_eq!(
+            session.last_ao5().map(|t| t.to_string()),
+            Some("12.00".to_string())
+        );
+        let removed = session.remove_solve(1).unwrap();
+        assert_eq!(removed.tags, Vec::<String>::new());
+        assert!(session.remove_solve(4).is_none());
+        assert_eq!(session.get_n_solves(), 4);
+        assert_eq!(session.last_ao5(), None);
+        assert_eq!(
+            session.last_mo3().map(|t| t.to_string()),
+            Some("12.66".to_string())
+        );
+
+        session.insert_solve(1, removed);
+        assert_eq!(
+            session.last_ao5().map(|t| t.to_string()),
+            Some("12.00".to_string())
+        );
+    }
+
+    #[test]
+    fn trim_averages_proportionally() {
+        let mut session = Session::new();
+        for time in ["DNF", "DNF"]
+            .into_iter()
+            .map(String::from)
+            .chain((10..48).map(|secs| secs.to_string()))
+        {
+            session.add_solve(Solve::new(time.parse().unwrap(), String::new()));
+        }
+
+        // 2 solves are trimmed from each end of an ao40.
+        assert_eq!(
+            session.best_average_of(40).map(|t| t.to_string()),
+            Some("29.50".to_string())
+        );
+        assert!(session.best_average_of(20).is_some_and(|t| !t.is_dnf()));
+
+        session.remove_solve(39);
+        assert_eq!(session.best_average_of(40), None);
+    }
+}
@@ -3,9 +3,13 @@ use std::{
     fmt::Display,
     iter::Sum,
     ops::{Add, Div, Sub},
+    str::FromStr,
     time::{Duration, SystemTime},
 };
 
+use super::cube::{Algorithm, Cube, Move, ParseMoveError, Turn};
+use super::relay::Relay;
+
 /// Penalty of a solve.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Penalty {
@@ -96,6 +100,59 @@ impl Display for SolveTime {
     }
 }
 
+/// An error which can be returned when parsing a [`SolveTime`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSolveTimeError {
+    pub input: String,
+}
+
+impl Display for ParseSolveTimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid time `{}`", self.input)
+    }
+}
+
+impl std::error::Error for ParseSolveTimeError {}
+
+impl FromStr for SolveTime {
+    type Err = ParseSolveTimeError;
+
+    /// Parse a time like `12.34`, `1:02.34`, `12.34+` or `DNF`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseSolveTimeError {
+            input: s.to_string(),
+        };
+
+        let trimmed = s.trim();
+        if trimmed.eq_ignore_ascii_case("dnf") {
+            return Ok(Self::new(Duration::ZERO, Some(Penalty::Dnf)));
+        }
+
+        let (trimmed, plus2) = match trimmed.strip_suffix('+') {
+            Some(t) => (t, true),
+            None => (trimmed, false),
+        };
+        let (minutes, seconds) = match trimmed.split_once(':') {
+            Some((m, s)) => (m.parse::<u64>().map_err(|_| err())?, s),
+            None => (0, trimmed),
+        };
+        let seconds = seconds.parse::<f64>().map_err(|_| err())?;
+        if !seconds.is_finite() || seconds < 0.0 || (minutes > 0 && seconds >= 60.0) {
+            return Err(err());
+        }
+
+        let millis = minutes * 60_000 + (seconds * 1000.0).round() as u64;
+        Ok(match plus2 {
+            // The recorded time includes the penalty.
+            true => Self::new(
+                Duration::from_millis(millis.checked_sub(2000).ok_or_else(err)?),
+                Some(Penalty::Plus2),
+            ),
+            false => Self::new(Duration::from_millis(millis), None),
+        })
+    }
+}
+
 impl Add for SolveTime {
     type Output = Self;
 
@@ -143,10 +200,228 @@ impl Sum<SolveTime> for SolveTime {
     }
 }
 
+/// Puzzle count of a multi-blind attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultiBld {
+    /// Number of puzzles solved.
+    pub solved: u32,
+    /// Number of puzzles attempted.
+    pub attempted: u32,
+}
+
+impl MultiBld {
+    /// Create a new `MultiBld`.
+    pub fn new(solved: u32, attempted: u32) -> Self {
+        Self {
+            solved: solved.min(attempted),
+            attempted,
+        }
+    }
+
+    /// Get the number of puzzles not solved.
+    pub fn unsolved(&self) -> u32 {
+        self.attempted - self.solved
+    }
+
+    /// Get the points of the attempt: puzzles solved minus puzzles not solved.
+    pub fn points(&self) -> i32 {
+        self.solved as i32 - self.unsolved() as i32
+    }
+
+    /// Return `true` if the attempt is DNF by WCA rules, that is when it scores
+    /// less than zero points or solves fewer than two puzzles.
+    pub fn is_dnf(&self) -> bool {
+        self.points() < 0 || self.solved < 2
+    }
+
+    /// Get the time limit of the attempt: 10 minutes per puzzle, up to an hour.
+    pub fn time_limit(&self) -> Duration {
+        Duration::from_secs(600 * self.attempted.min(6) as u64)
+    }
+}
+
+/// Result of a multi-blind attempt, ranked by WCA rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultiBldResult {
+    pub cubes: MultiBld,
+    pub time: SolveTime,
+}
+
+impl MultiBldResult {
+    /// Return `true` if the attempt is DNF.
+    pub fn is_dnf(&self) -> bool {
+        self.cubes.is_dnf() || self.time.is_dnf()
+    }
+
+    /// Get the recorded time in whole seconds, as used for ranking.
+    pub fn recorded_seconds(&self) -> Option<u64> {
+        self.time.recorded_time().map(|t| t.as_secs())
+    }
+}
+
+impl PartialOrd for MultiBldResult {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MultiBldResult {
+    /// Better results are smaller: more points first, then less time, then
+    /// fewer puzzles not solved.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_dnf(), other.is_dnf()) {
+            (false, true) => return Ordering::Less,
+            (true, false) => return Ordering::Greater,
+            (true, true) => return Ordering::Equal,
+            (false, false) => {}
+        }
+
+        other
+            .cubes
+            .points()
+            .cmp(&self.cubes.points())
+            .then_with(|| self.recorded_seconds().cmp(&other.recorded_seconds()))
+            .then_with(|| self.cubes.unsolved().cmp(&other.cubes.unsolved()))
+    }
+}
+
+impl Display for MultiBldResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_dnf() {
+            return write!(f, "DNF");
+        }
+
+        let seconds = self.recorded_seconds().unwrap_or_default();
+        write!(
+            f,
+            "{}/{} {}:{:02}",
+            self.cubes.solved,
+            self.cubes.attempted,
+            seconds / 60,
+            seconds % 60
+        )
+    }
+}
+
+/// Blindfolded metadata of a solve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bld {
+    /// Time spent memorizing, if the memo split was recorded.
+    pub memo: Option<Duration>,
+    /// Puzzle count, if the solve is a multi-blind attempt.
+    pub multi: Option<MultiBld>,
+}
+
+/// An error in a fewest moves solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolutionError {
+    /// The solution or the scramble contains an invalid move.
+    Invalid(ParseMoveError),
+    /// The solution contains a slice move, which fewest moves does not allow.
+    SliceMove(Move),
+    /// The solution has more moves than allowed.
+    TooLong { moves: u32 },
+    /// The solution does not solve the scramble.
+    Unsolved { moves: u32 },
+}
+
+impl Display for SolutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "{}", e),
+            Self::SliceMove(m) => write!(f, "slice moves like {} are not allowed", m),
+            Self::TooLong { moves } => {
+                write!(f, "{} moves, over the limit of {}", moves, Fmc::MAX_MOVES)
+            }
+            Self::Unsolved { moves } => {
+                write!(f, "{} moves, does not solve the scramble", moves)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolutionError {}
+
+/// Fewest moves metadata of a solve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fmc {
+    /// The submitted solution.
+    pub solution: String,
+    /// Move count of the solution, or `None` if it does not solve the scramble.
+    pub moves: Option<u32>,
+}
+
+impl Fmc {
+    /// Maximum move count of a solution.
+    pub const MAX_MOVES: u32 = 80;
+
+    /// Create a new `Fmc`, verifying the solution against the scramble.
+    pub fn new(scramble: &str, solution: &str) -> Self {
+        Self {
+            solution: solution.trim().to_string(),
+            moves: Self::check_solution(scramble, solution).ok(),
+        }
+    }
+
+    /// Check that the solution solves the scramble on a 3x3x3 cube, and get
+    /// its move count in OBTM.
+    ///
+    /// As in official fewest moves, slice moves are not allowed and the solution
+    /// can have at most 80 moves.
+    pub fn check_solution(scramble: &str, solution: &str) -> Result<u32, SolutionError> {
+        let scramble: Algorithm = scramble.parse().map_err(SolutionError::Invalid)?;
+        let solution: Algorithm = solution.parse().map_err(SolutionError::Invalid)?;
+        if let Some(m) = solution
+            .moves
+            .iter()
+            .find(|m| matches!(m.turn, Turn::Slice(_)))
+        {
+            return Err(SolutionError::SliceMove(*m));
+        }
+        let moves = solution.move_count();
+        if moves > Self::MAX_MOVES {
+            return Err(SolutionError::TooLong { moves });
+        }
+
+        let mut cube = Cube::new(3);
+        cube.apply(&scramble);
+        cube.apply(&solution);
+
+        if cube.is_solved() {
+            Ok(moves)
+        } else {
+            Err(SolutionError::Unsolved { moves })
+        }
+    }
+
+    /// Return `true` if the solution does not solve the scramble.
+    pub fn is_dnf(&self) -> bool {
+        self.moves.is_none()
+    }
+}
+
+/// Mean move count of fewest moves solves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveMean {
+    Moves(f32),
+    Dnf,
+}
+
+impl Display for MoveMean {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Moves(moves) => write!(f, "{:.2}", moves),
+            Self::Dnf => write!(f, "DNF"),
+        }
+    }
+}
+
 /// A trait for a sequence of solves.
 pub trait SolvesSeq {
     fn mean_of_n(&self) -> Option<SolveTime>;
     fn average_of_n(&self) -> Option<SolveTime>;
+    /// Get the mean move count, if every solve is a fewest moves solve.
+    fn mean_of_moves(&self) -> Option<MoveMean>;
 }
 
 /// A solve.
@@ -155,6 +430,108 @@ pub struct Solve {
     pub time: SolveTime,
     pub timestamp: SystemTime,
     pub scramble: String,
+    /// Blindfolded metadata, if the solve is a blindfolded attempt.
+    pub bld: Option<Bld>,
+    /// Fewest moves metadata, if the solve is a fewest moves attempt.
+    pub fmc: Option<Fmc>,
+    /// Relay metadata, if the solve is a relay attempt.
+    pub relay: Option<Relay>,
+    /// Free-text comment.
+    pub comment: String,
+    /// User-defined tags, like "lockup" or "pll skip", in the order they were added.
+    pub tags: Vec<String>,
+}
+
+impl Solve {
+    /// Create a solve done now, without metadata, comment or tags.
+    pub fn new(time: SolveTime, scramble: String) -> Self {
+        Self {
+            time,
+            timestamp: SystemTime::now(),
+            scramble,
+            bld: None,
+            fmc: None,
+            relay: None,
+            comment: String::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// Get the memorization time, if recorded.
+    pub fn memo_time(&self) -> Option<Duration> {
+        self.bld.and_then(|bld| bld.memo)
+    }
+
+    /// Get the execution time, that is the measured time after memorization.
+    pub fn execution_time(&self) -> Option<Duration> {
+        self.memo_time()
+            .map(|memo| self.time.measured_time().saturating_sub(memo))
+    }
+
+    /// Get the multi-blind result, if the solve is a multi-blind attempt.
+    pub fn multi_bld(&self) -> Option<MultiBldResult> {
+        self.bld
+            .and_then(|bld| bld.multi)
+            .map(|cubes| MultiBldResult {
+                cubes,
+                time: self.time,
+            })
+    }
+
+    /// Return `true` if the solve is a multi-blind attempt.
+    pub fn is_multi_bld(&self) -> bool {
+        self.bld.is_some_and(|bld| bld.multi.is_some())
+    }
+
+    /// Return `true` if the solve is a fewest moves attempt.
+    pub fn is_fmc(&self) -> bool {
+        self.fmc.is_some()
+    }
+
+    /// Return `true` if the result of the solve is its time, so that it can be
+    /// averaged with other timed solves.
+    pub fn is_timed(&self) -> bool {
+        !self.is_multi_bld() && !self.is_fmc()
+    }
+
+    /// Return `true` if the solve has a tag, ignoring case.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// Add a tag to the solve, unless it is blank or the solve already has it.
+    ///
+    /// Returns `true` if the tag was added.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() || self.has_tag(tag) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        true
+    }
+
+    /// Remove a tag from the solve, ignoring case.
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.retain(|t| !t.eq_ignore_ascii_case(tag.trim()));
+    }
+}
+
+impl Display for Solve {
+    /// Display the result of the solve.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(fmc) = &self.fmc {
+            return match fmc.moves {
+                Some(moves) => write!(f, "{}", moves),
+                None => write!(f, "DNF"),
+            };
+        }
+
+        match self.multi_bld() {
+            Some(result) => write!(f, "{}", result),
+            None => write!(f, "{}", self.time),
+        }
+    }
 }
 
 impl SolvesSeq for &[Solve] {
@@ -169,22 +546,46 @@ impl SolvesSeq for &[Solve] {
     }
 
     fn average_of_n(&self) -> Option<SolveTime> {
-        let len = self.len() as u32;
-        if len < 3 {
-            return None;
+        trimmed_average(self.iter().map(|s| s.time))
+    }
+
+    fn mean_of_moves(&self) -> Option<MoveMean> {
+        mean_of_moves(self.iter())
+    }
+}
+
+/// Get the average of at least 3 times, trimming 5% of the times, rounded up, from each end.
+///
+/// This trims the best and worst time of an ao5 or ao12, and 5 times from each end of an
+/// ao100, like WCA-style averages of other timers.
+pub(super) fn trimmed_average(times: impl Iterator<Item = SolveTime>) -> Option<SolveTime> {
+    let mut times: Vec<_> = times.collect();
+    let len = times.len();
+    if len < 3 {
+        return None;
+    }
+
+    times.sort_unstable();
+    let trim = len.div_ceil(20);
+    let sum: SolveTime = times[trim..len - trim].iter().copied().sum();
+    Some(sum / (len - 2 * trim) as u32)
+}
+
+/// Get the mean move count of fewest moves solves.
+pub(super) fn mean_of_moves<'a>(solves: impl Iterator<Item = &'a Solve>) -> Option<MoveMean> {
+    let mut len = 0;
+    let mut sum = 0;
+    for solve in solves {
+        match solve.fmc.as_ref()?.moves {
+            Some(moves) => sum += moves,
+            None => return Some(MoveMean::Dnf),
         }
+        len += 1;
+    }
 
-        let it = self.iter().map(|s| s.time).enumerate();
-        let (imax, _max) = it.clone().max_by_key(|&(_, st)| st)?;
-        let (imin, _min) = it.clone().min_by_key(|&(_, st)| st)?;
-        let sum = it.fold(SolveTime::default(), |acc, (i, st)| {
-            if i != imax && i != imin {
-                acc + st
-            } else {
-                acc
-            }
-        });
-        Some(sum / (len - 2))
+    match len {
+        0 => None,
+        _ => Some(MoveMean::Moves(sum as f32 / len as f32)),
     }
 }
 
@@ -232,6 +633,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_solve_time() {
+        let parse = |s: &str| s.parse::<SolveTime>().map(|st| st.to_string());
+        assert_eq!(parse("12.34"), Ok("12.34".to_string()));
+        assert_eq!(parse(" 1:02.5 "), Ok("1:02.50".to_string()));
+        assert_eq!(parse("15"), Ok("15.00".to_string()));
+        assert_eq!(parse("14.20+"), Ok("14.20+".to_string()));
+        assert_eq!(parse("dnf"), Ok("DNF".to_string()));
+        assert!(parse("1:75").is_err());
+        assert!(parse("1.5+").is_err());
+        assert!(parse("fast").is_err());
+    }
+
     #[test]
     fn display_solve_time() {
         assert_eq!(
@@ -259,4 +673,78 @@ mod test {
             "DNF".to_string(),
         );
     }
+
+    #[test]
+    fn multi_bld_points() {
+        assert_eq!(MultiBld::new(8, 10).points(), 6);
+        assert!(!MultiBld::new(2, 2).is_dnf());
+        assert!(!MultiBld::new(2, 4).is_dnf());
+        assert!(MultiBld::new(2, 5).is_dnf());
+        assert!(MultiBld::new(1, 2).is_dnf());
+        assert!(MultiBld::new(1, 1).is_dnf());
+    }
+
+    #[test]
+    fn rank_multi_bld_results() {
+        let result = |solved, attempted, secs| MultiBldResult {
+            cubes: MultiBld::new(solved, attempted),
+            time: SolveTime::new(Duration::from_secs(secs), None),
+        };
+
+        // More points wins, regardless of time.
+        assert!(result(9, 10, 3_500) < result(7, 7, 1_800));
+        // Same points, less time wins.
+        assert!(result(8, 10, 3_000) < result(8, 10, 3_100));
+        // Same points and time, fewer unsolved puzzles wins.
+        assert!(result(6, 6, 3_000) < result(7, 8, 3_000));
+        // DNF ranks last.
+        assert!(result(3, 10, 100) > result(2, 2, 3_000));
+
+        assert_eq!(result(8, 10, 3_492).to_string(), "8/10 58:12".to_string());
+        assert_eq!(result(1, 2, 600).to_string(), "DNF".to_string());
+    }
+
+    #[test]
+    fn check_fmc_solution() {
+        let scramble = "R U R' U' F2 D L'";
+        assert_eq!(Fmc::check_solution(scramble, "L D' F2 U R U' R'"), Ok(7));
+        // Rotations are free, and the cube may end in any orientation.
+        assert_eq!(
+            Fmc::check_solution(scramble, "L x x' D' F2 U R U' R' z2"),
+            Ok(7)
+        );
+        assert_eq!(
+            Fmc::check_solution(scramble, "L D' F2 U R U'"),
+            Err(SolutionError::Unsolved { moves: 6 }),
+        );
+        assert!(matches!(
+            Fmc::check_solution(scramble, "L D' F3"),
+            Err(SolutionError::Invalid(_)),
+        ));
+        assert!(matches!(
+            Fmc::check_solution("R", "M x' r'"),
+            Err(SolutionError::SliceMove(_)),
+        ));
+        // Solves the scramble, but in more than 80 moves.
+        let long = format!("{}R'", "R U R' U' ".repeat(24));
+        assert_eq!(
+            Fmc::check_solution("R", &long),
+            Err(SolutionError::TooLong { moves: 97 }),
+        );
+
+        let fmc = |moves| Solve {
+            fmc: Some(Fmc {
+                solution: String::new(),
+                moves,
+            }),
+            ..Solve::new(SolveTime::default(), scramble.to_string())
+        };
+        let solves = [fmc(Some(28)), fmc(Some(30)), fmc(Some(27))];
+        assert_eq!(
+            (&solves[..]).mean_of_moves().map(|m| m.to_string()),
+            Some("28.33".to_string()),
+        );
+        let solves = [fmc(Some(28)), fmc(None), fmc(Some(27))];
+        assert_eq!((&solves[..]).mean_of_moves(), Some(MoveMean::Dnf));
+    }
 }
@@ -0,0 +1,489 @@
+use std::fmt::Display;
+
+use once_cell::sync::Lazy;
+use rand::{seq::SliceRandom, Rng};
+
+use super::cube::{Algorithm, Amount, Cube, Face, Move, Turn};
+use super::SolveTime;
+
+/// A last-layer case, with moves solving it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Case {
+    pub name: &'static str,
+    pub alg: &'static str,
+}
+
+const fn case(name: &'static str, alg: &'static str) -> Case {
+    Case { name, alg }
+}
+
+const OLL_CASES: [Case; 57] = [
+    case("OLL 1", "R U2 R2 F R F' U2 R' F R F'"),
+    case("OLL 2", "F R U R' U' F' f R U R' U' f'"),
+    case("OLL 3", "f R U R' U' f' U' F R U R' U' F'"),
+    case("OLL 4", "f R U R' U' f' U F R U R' U' F'"),
+    case("OLL 5", "r' U2 R U R' U r"),
+    case("OLL 6", "r U2 R' U' R U' r'"),
+    case("OLL 7", "r U R' U R U2 r'"),
+    case("OLL 8", "l' U' L U' L' U2 l"),
+    case("OLL 9", "R U R' U' R' F R2 U R' U' F'"),
+    case("OLL 10", "R U R' U R' F R F' R U2 R'"),
+    case("OLL 11", "r U R' U R' F R F' R U2 r'"),
+    case("OLL 12", "M' R' U' R U' R' U2 R U' R r'"),
+    case("OLL 13", "F U R U' R2 F' R U R U' R'"),
+    case("OLL 14", "R' F R U R' F' R F U' F'"),
+    case("OLL 15", "r' U' r R' U' R U r' U r"),
+    case("OLL 16", "r U r' R U R' U' r U' r'"),
+    case("OLL 17", "R U R' U R' F R F' U2 R' F R F'"),
+    case("OLL 18", "r U R' U R U2 r2 U' R U' R' U2 r"),
+    case("OLL 19", "r' R U R U R' U' M' R' F R F'"),
+    case("OLL 20", "r U R' U' M2 U R U' R' U' M'"),
+    case("OLL 21", "R U2 R' U' R U R' U' R U' R'"),
+    case("OLL 22", "R U2 R2 U' R2 U' R2 U2 R"),
+    case("OLL 23", "R2 D' R U2 R' D R U2 R"),
+    case("OLL 24", "r U R' U' r' F R F'"),
+    case("OLL 25", "F' r U R' U' r' F R"),
+    case("OLL 26", "R U2 R' U' R U' R'"),
+    case("OLL 27", "R U R' U R U2 R'"),
+    case("OLL 28", "r U R' U' r' R U R U' R'"),
+    case("OLL 29", "R U R' U' R U' R' F' U' F R U R'"),
+    case("OLL 30", "F R' F R2 U' R' U' R U R' F2"),
+    case("OLL 31", "R' U' F U R U' R' F' R"),
+    case("OLL 32", "L U F' U' L' U L F L'"),
+    case("OLL 33", "R U R' U' R' F R F'"),
+    case("OLL 34", "R U R2 U' R' F R U R U' F'"),
+    case("OLL 35", "R U2 R2 F R F' R U2 R'"),
+    case("OLL 36", "L' U' L U' L' U L U L F' L' F"),
+    case("OLL 37", "F R' F' R U R U' R'"),
+    case("OLL 38", "R U R' U R U' R' U' R' F R F'"),
+    case("OLL 39", "L F' L' U' L U F U' L'"),
+    case("OLL 40", "R' F R U R' U' F' U R"),
+    case("OLL 41", "R U R' U R U2 R' F R U R' U' F'"),
+    case("OLL 42", "R' U' R U' R' U2 R F R U R' U' F'"),
+    case("OLL 43", "F' U' L' U L F"),
+    case("OLL 44", "F U R U' R' F'"),
+    case("OLL 45", "F R U R' U' F'"),
+    case("OLL 46", "R' U' R' F R F' U R"),
+    case("OLL 47", "R' U' R' F R F' R' F R F' U R"),
+    case("OLL 48", "F R U R' U' R U R' U' F'"),
+    case("OLL 49", "r U' r2 U r2 U r2 U' r"),
+    case("OLL 50", "r' U r2 U' r2 U' r2 U r'"),
+    case("OLL 51", "F U R U' R' U R U' R' F'"),
+    case("OLL 52", "R U R' U R U' B U' B' R'"),
+    case("OLL 53", "r' U' R U' R' U R U' R' U2 r"),
+    case("OLL 54", "r U R' U R U' R' U R U2 r'"),
+    case("OLL 55", "R' F R U R U' R2 F' R2 U' R' U R U R'"),
+    case("OLL 56", "r' U' r U' R' U R U' R' U R r' U r"),
+    case("OLL 57", "R U R' U' M' U R U' r'"),
+];
+
+const PLL_CASES: [Case; 21] = [
+    case("Aa", "R' F R' B2 R F' R' B2 R2"),
+    case("Ab", "R2 B2 R F R' B2 R F' R"),
+    case("E", "R' U L' D2 L U' R L' U R' D2 R U' L"),
+    case("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    case("Ga", "R2 U R' U R' U' R U' R2 D U' R' U R D'"),
+    case("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    case("Gc", "R2 U' R U' R U R' U R2 D' U R U' R' D"),
+    case("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    case("H", "M2 U M2 U2 M2 U M2"),
+    case("Ja", "L' U' L F L' U' L U L F' L2 U L"),
+    case("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    case(
+        "Na",
+        "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    ),
+    case("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    case("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    case("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    case("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    case("Ua", "R U' R U R U R U' R' U' R2"),
+    case("Ub", "R2 U R U R' U' R' U' R' U R'"),
+    case("V", "R U' R U R' D R D' R U' D R2 U R2 D' R2"),
+    case("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    case("Z", "M' U M2 U M2 U M' U2 M2"),
+];
+
+/// Algorithms orienting the corners when the edges are oriented, by shape of
+/// the oriented corners.
+const OCLL_CASES: [Case; 7] = [
+    case("T", "r U R' U' r' F R F'"),
+    case("U", "R2 D' R U2 R' D R U2 R"),
+    case("L", "F' r U R' U' r' F R"),
+    case("H", "R U2 R' U' R U R' U' R U' R'"),
+    case("Pi", "R U2 R2 U' R2 U' R2 U2 R"),
+    case("S", "R U R' U R U2 R'"),
+    case("AS", "R U2 R' U' R U' R'"),
+];
+
+/// The 472 ZBLL cases, named by their corner orientation and two-look solution.
+static ZBLL_CASES: Lazy<Vec<Case>> = Lazy::new(zbll_cases);
+
+/// Enumerate the ZBLL cases, each solved by an OCLL algorithm followed by an
+/// adjustment of the upper face and a PLL algorithm.
+///
+/// These are not ZBLL algorithms, and the names are not the usual ones: a case named
+/// `ZBLL T: U2 + Jb` is solved by the T OCLL, `U2` and the Jb PLL.
+///
+/// Cases that only differ by adjustments of the upper face before or after
+/// the algorithm are the same case.
+fn zbll_cases() -> Vec<Case> {
+    let parse = |alg: &str| alg.parse::<Algorithm>().expect("case algorithms are valid");
+    let u = |amount| Move::new(Turn::Face(Face::U), amount);
+    let aufs = [
+        None,
+        Some(u(Amount::Single)),
+        Some(u(Amount::Double)),
+        Some(u(Amount::Inverse)),
+    ];
+
+    let mut cases = Vec::new();
+    for ocll in OCLL_CASES {
+        let mut states: Vec<Cube> = Vec::new();
+        for pll in std::iter::once(None).chain(PLL_CASES.iter().map(Some)) {
+            for auf in aufs {
+                let mut moves = parse(ocll.alg).moves;
+                moves.extend(auf);
+                if let Some(pll) = pll {
+                    moves.extend(parse(pll.alg).moves);
+                }
+                let alg = Algorithm::new(moves).simplified();
+
+                let mut variants = aufs.iter().flat_map(|pre| {
+                    let mut cube = Cube::new(3);
+                    if let Some(pre) = pre {
+                        cube.apply_move(pre);
+                    }
+                    cube.apply(&alg.inverse());
+                    (0..4).map(move |_| {
+                        cube.apply_move(&u(Amount::Single));
+                        cube.clone()
+                    })
+                });
+                if variants.any(|variant| states.contains(&variant)) {
+                    continue;
+                }
+
+                let mut cube = Cube::new(3);
+                cube.apply(&alg.inverse());
+                states.push(cube);
+                let solution = match (auf, pll) {
+                    (_, None) => String::from("no PLL"),
+                    (None, Some(pll)) => pll.name.to_string(),
+                    (Some(auf), Some(pll)) => format!("{} + {}", auf, pll.name),
+                };
+                // Cases live as long as the program, like the other case sets.
+                cases.push(Case {
+                    name: Box::leak(format!("ZBLL {}: {}", ocll.name, solution).into()),
+                    alg: Box::leak(alg.to_string().into()),
+                });
+            }
+        }
+    }
+    cases
+}
+
+/// A set of last-layer cases to train.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CaseSet {
+    Oll,
+    #[default]
+    Pll,
+    Zbll,
+}
+
+impl CaseSet {
+    pub const ALL: [Self; 3] = [Self::Oll, Self::Pll, Self::Zbll];
+
+    pub fn cases(&self) -> &'static [Case] {
+        match self {
+            Self::Oll => &OLL_CASES,
+            Self::Pll => &PLL_CASES,
+            Self::Zbll => &ZBLL_CASES[..],
+        }
+    }
+
+    /// Describe the moves solving the cases of the set.
+    pub fn solution_label(&self) -> &'static str {
+        match self {
+            Self::Oll | Self::Pll => "Algorithm",
+            Self::Zbll => "Two-look solution (OCLL, U turn, PLL)",
+        }
+    }
+
+    /// Generate a scramble leading to a case of the set, with random adjustments
+    /// of the last layer.
+    pub fn scramble<R: Rng + ?Sized>(&self, rng: &mut R, case: &Case) -> Algorithm {
+        let alg: Algorithm = case.alg.parse().expect("case algorithms are valid");
+        let mut solution: Vec<Move> = random_auf(rng).into_iter().collect();
+        solution.extend(alg.moves);
+        solution.extend(random_auf(rng));
+
+        Algorithm::new(solution).inverse().simplified()
+    }
+}
+
+/// Get a random adjustment of the upper face, which may be no move.
+fn random_auf<R: Rng + ?Sized>(rng: &mut R) -> Option<Move> {
+    [
+        None,
+        Some(Amount::Single),
+        Some(Amount::Double),
+        Some(Amount::Inverse),
+    ]
+    .choose(rng)
+    .unwrap()
+    .map(|amount| Move::new(Turn::Face(Face::U), amount))
+}
+
+impl Display for CaseSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Oll => "OLL",
+                Self::Pll => "PLL",
+                Self::Zbll => "ZBLL",
+            }
+        )
+    }
+}
+
+/// Training results of a case.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CaseStats {
+    pub times: Vec<SolveTime>,
+    /// Number of attempts where the case was not recognized correctly.
+    pub mistakes: u32,
+}
+
+impl CaseStats {
+    /// Get the mean time of the case, ignoring DNFs.
+    pub fn mean(&self) -> Option<SolveTime> {
+        let times: Vec<_> = self.times.iter().filter(|t| !t.is_dnf()).copied().collect();
+        match times.len() {
+            0 => None,
+            len => Some(times.into_iter().sum::<SolveTime>() / len as u32),
+        }
+    }
+}
+
+/// Trains the cases of a set, independently of sessions.
+#[derive(Debug, Clone)]
+pub struct Trainer {
+    pub set: CaseSet,
+    /// Whether each case of the set is trained.
+    selected: Vec<bool>,
+    stats: Vec<CaseStats>,
+    /// Index of the case being trained.
+    current: usize,
+    pub scramble: Algorithm,
+    /// Index of the last trained case.
+    last: Option<usize>,
+    /// Whether the last attempt was already marked as a recognition mistake.
+    last_mistake: bool,
+}
+
+impl Trainer {
+    pub fn new(set: CaseSet) -> Self {
+        let n = set.cases().len();
+        let mut trainer = Self {
+            set,
+            selected: vec![true; n],
+            stats: vec![CaseStats::default(); n],
+            current: 0,
+            scramble: Algorithm::default(),
+            last: None,
+            last_mistake: false,
+        };
+        trainer.next_case(&mut rand::thread_rng());
+        trainer
+    }
+
+    pub fn current(&self) -> &'static Case {
+        &self.set.cases()[self.current]
+    }
+
+    pub fn last(&self) -> Option<&'static Case> {
+        self.last.map(|i| &self.set.cases()[i])
+    }
+
+    pub fn stats(&self, index: usize) -> &CaseStats {
+        &self.stats[index]
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected[index]
+    }
+
+    /// Select or deselect a case. At least one case stays selected.
+    pub fn set_selected(&mut self, index: usize, selected: bool) {
+        if selected || self.selected.iter().filter(|&&s| s).count() > 1 {
+            self.selected[index] = selected;
+        }
+        if !self.selected[self.current] {
+            self.next_case(&mut rand::thread_rng());
+        }
+    }
+
+    /// Record the time of the current case and move to the next one.
+    pub fn record(&mut self, time: SolveTime) {
+        self.stats[self.current].times.push(time);
+        self.last = Some(self.current);
+        self.last_mistake = false;
+        self.next_case(&mut rand::thread_rng());
+    }
+
+    /// Return `true` if the last attempt can be marked as a recognition mistake.
+    pub fn can_mark_mistake(&self) -> bool {
+        self.last.is_some() && !self.last_mistake
+    }
+
+    /// Mark the last attempt as not recognized correctly, once per attempt.
+    pub fn mark_mistake(&mut self) {
+        if let Some(last) = self.last.filter(|_| !self.last_mistake) {
+            self.stats[last].mistakes += 1;
+            self.last_mistake = true;
+        }
+    }
+
+    /// Get how likely a case is to be chosen, relative to other cases.
+    ///
+    /// Untrained cases come first, then cases slower than the mean of the set
+    /// and cases with recognition mistakes.
+    pub fn weight(&self, index: usize) -> f64 {
+        self.weight_with_mean(index, self.selected_mean())
+    }
+
+    /// Get the mean of the means of the selected cases.
+    fn selected_mean(&self) -> Option<SolveTime> {
+        let means: Vec<_> = (0..self.stats.len())
+            .filter(|&i| self.selected[i])
+            .filter_map(|i| self.stats[i].mean())
+            .collect();
+        match means.len() {
+            0 => None,
+            len => Some(means.into_iter().sum::<SolveTime>() / len as u32),
+        }
+    }
+
+    fn weight_with_mean(&self, index: usize, overall: Option<SolveTime>) -> f64 {
+        let stats = &self.stats[index];
+        let (Some(mean), Some(overall)) = (stats.mean(), overall) else {
+            return 4.0;
+        };
+
+        let ratio = match overall.time.as_secs_f64() {
+            overall if overall > 0.0 => mean.time.as_secs_f64() / overall,
+            _ => 1.0,
+        };
+        let mistake_rate = stats.mistakes as f64 / stats.times.len() as f64;
+
+        (ratio * ratio * (1.0 + 2.0 * mistake_rate)).max(0.25)
+    }
+
+    /// Choose the next case, biased toward weak cases, and generate its scramble.
+    pub fn next_case<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let selected: Vec<usize> = (0..self.selected.len())
+            .filter(|&i| self.selected[i])
+            .collect();
+        let overall = self.selected_mean();
+        if let Ok(&next) = selected.choose_weighted(rng, |&i| self.weight_with_mean(i, overall)) {
+            self.current = next;
+        }
+        self.scramble = self.set.scramble(rng, self.current());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::data::Cube;
+
+    #[test]
+    fn case_scrambles() {
+        let mut rng = rand::thread_rng();
+        for set in CaseSet::ALL {
+            for case in set.cases() {
+                let alg: Algorithm = case.alg.parse().unwrap();
+                let mut cube = Cube::new(3);
+                cube.apply(&alg);
+                assert!(cube.is_solved_below_top(), "{} {}", set, case.name);
+                if set == CaseSet::Pll {
+                    assert!(cube.is_face_solved(Face::U), "{}", case.name);
+                }
+
+                let scramble = set.scramble(&mut rng, case);
+                let mut cube = Cube::new(3);
+                cube.apply(&scramble);
+                assert!(cube.is_solved_below_top(), "{} {}", case.name, scramble);
+                assert!(!cube.is_solved(), "{} {}", case.name, scramble);
+            }
+        }
+    }
+
+    #[test]
+    fn weak_cases_are_weighted() {
+        let mut trainer = Trainer::new(CaseSet::Pll);
+        let time = |secs| SolveTime::new(Duration::from_secs(secs), None);
+        trainer.stats[0].times.push(time(2));
+        trainer.stats[1].times.push(time(4));
+        assert_eq!(trainer.weight(2), 4.0);
+        assert!(trainer.weight(1) > trainer.weight(0));
+
+        trainer.stats[0].mistakes = 1;
+        assert!(trainer.weight(0) > 0.25);
+
+        trainer.set_selected(0, false);
+        for i in 1..PLL_CASES.len() {
+            trainer.set_selected(i, false);
+        }
+        assert!(trainer.is_selected(PLL_CASES.len() - 1));
+        assert_eq!(trainer.current().name, "Z");
+    }
+
+    #[test]
+    fn zbll_cases() {
+        let count = |group: &str| {
+            let prefix = format!("ZBLL {}: ", group);
+            CaseSet::Zbll
+                .cases()
+                .iter()
+                .filter(|case| case.name.starts_with(&prefix))
+                .count()
+        };
+        assert_eq!(CaseSet::Zbll.cases().len(), 472);
+        assert_eq!(count("H"), 40);
+        assert_eq!(count("T"), 72);
+        assert_eq!(count("AS"), 72);
+
+        let cases = CaseSet::Zbll.cases();
+        assert!(cases
+            .iter()
+            .enumerate()
+            .all(|(i, case)| cases[..i].iter().all(|c| c.name != case.name)));
+        assert!(cases.iter().any(|case| case.name == "ZBLL H: no PLL"));
+        assert!(cases.iter().any(|case| case.name == "ZBLL T: U2 + Jb"));
+    }
+
+    #[test]
+    fn one_mistake_per_attempt() {
+        let mut trainer = Trainer::new(CaseSet::Oll);
+        trainer.mark_mistake();
+        assert!(!trainer.can_mark_mistake());
+
+        trainer.record(SolveTime::new(Duration::from_secs(3), None));
+        let last = trainer
+            .set
+            .cases()
+            .iter()
+            .position(|c| Some(c) == trainer.last());
+        trainer.mark_mistake();
+        trainer.mark_mistake();
+        assert!(!trainer.can_mark_mistake());
+        assert_eq!(trainer.stats(last.unwrap()).mistakes, 1);
+    }
+}
@@ -1,33 +1,210 @@
 use iced::{
-    alignment,
+    alignment, event,
     keyboard::{self, KeyCode},
-    subscription, time, widget, Alignment, Application, Command, Event, Length, Subscription,
+    subscription, time,
+    widget::{self, pane_grid},
+    window, Alignment, Application, Command, Event, Length, Subscription,
 };
-use std::time::{Duration, Instant, SystemTime};
+use std::time::{Duration, Instant};
 
 use crate::data;
 
 use crate::tangible;
+use crate::tangible::pattern::stat_row;
 
-pub use preferences::Preferences;
+pub use preferences::{
+    Averages, HoldTime, Inspection, Precision, Preferences, ReadyCue, StartTrigger, TimerDisplay,
+    TriggerKey,
+};
+pub use timer::{State, Timer};
+
+use competition::{Cutoff, TimeLimit};
+use ghost::GhostChoice;
+use goals::GoalForm;
+use layout::{LayoutPreset, LayoutSize, PaneAction, Panel};
+use shortcuts::{Dialog, Key, Shortcut};
+use solve_detail::TagFilter;
+use theme_editor::{ColorField, ThemeEditor};
+use timer::Press;
 
+mod battle;
+mod competition;
+mod focus;
+mod ghost;
+mod goals;
+mod history;
+mod layout;
+mod panels;
 mod preferences;
+mod scramble_queue;
+mod search;
+mod shortcuts;
+mod solve_detail;
+mod system_theme;
+mod theme_editor;
+mod themes;
+mod timer;
+mod trainer;
 
 pub struct KTApplication {
     preferences: Preferences,
+    /// Averages being edited in the preferences page.
+    averages_input: String,
+    averages_error: Option<String>,
+    /// Theme of the desktop, used by [`tangible::Theme::System`].
+    system_theme: tangible::Theme,
+    /// Themes read from the themes directory.
+    custom_themes: Vec<tangible::Theme>,
+    /// Why some theme files could not be read.
+    theme_errors: Vec<String>,
+    theme_editor: ThemeEditor,
+    page: Page,
+    /// Panels of the timer page.
+    panes: pane_grid::State<Panel>,
+    /// Panels of the timer page in small windows.
+    compact_panes: pane_grid::State<Panel>,
+    /// Number of changes made to the panes, to save the layout once resizing ends.
+    layout_changes: u64,
+    dialog: Option<Dialog>,
 
-    solve_time: data::SolveTime,
+    timer: Timer,
     link_to_last_solve: bool,
-    last_pressed: Instant,
-    state: State,
     session: data::Session,
+    previous_sessions: Vec<data::Session>,
+    /// Index of the solve whose details are shown.
+    selected_solve: Option<usize>,
+    /// The last deleted solve with its index, until it is put back or dismissed.
+    deleted_solve: Option<(usize, data::Solve)>,
+    tag_input: String,
+    tag_filter: TagFilter,
+    /// Search query over the solves of the session.
+    search: String,
+    /// Parsed search, or `None` if the search is empty or invalid.
+    search_query: Option<data::SolveQuery>,
+    search_error: Option<String>,
+    /// Solves matching the tag filter and the search with their indices in the session,
+    /// or `None` if the solves are not filtered.
+    filtered_solves: Option<(data::Session, Vec<usize>)>,
+    ghost_choice: GhostChoice,
+    ghost: Option<data::Ghost>,
+
+    mode: TimerMode,
+    scramble: data::Algorithm,
+    /// Imported scrambles, used before generated ones.
+    scramble_queue: Option<data::ScrambleQueue>,
+    scramble_file: String,
+    scramble_queue_error: Option<String>,
+    memo_time: Option<Duration>,
+    multi_bld: data::MultiBld,
+    fmc_solution: String,
+    relay_set: data::RelaySet,
+    relay_scrambles: Vec<String>,
+    relay_marks: Vec<Duration>,
+    relay_splits: bool,
+    battle: battle::Battle,
+    round: data::Round,
+    /// Finished competition rounds, recorded separately from the session.
+    round_results: Vec<data::Round>,
+    goals: Vec<data::Goal>,
+    goal_form: GoalForm,
+    goal_notification: Option<String>,
+    trainer: data::Trainer,
+}
+
+/// The kind of attempt being timed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimerMode {
+    #[default]
+    Standard,
+    /// Blindfolded solve, where the first press marks the end of memorization.
+    Blindfolded,
+    /// Multi-blind attempt, where the puzzle count is recorded with the time.
+    MultiBlind,
+    /// Fewest moves attempt, where a written solution is submitted within an hour.
+    FewestMoves,
+    /// Relay attempt, where several puzzles are solved in sequence.
+    Relay,
+    /// Two players racing on the same scramble, each with their own key and session.
+    Battle,
+    /// Competition round with a cutoff and a time limit, recorded apart from the session.
+    Competition,
+    /// Last-layer case training, recorded apart from the session.
+    Trainer,
+}
+
+impl TimerMode {
+    pub const ALL: [Self; 8] = [
+        Self::Standard,
+        Self::Blindfolded,
+        Self::MultiBlind,
+        Self::FewestMoves,
+        Self::Relay,
+        Self::Battle,
+        Self::Competition,
+        Self::Trainer,
+    ];
+
+    /// Return `true` if attempts use the shared scramble, which may come from the
+    /// imported queue, rather than scrambles of their own.
+    pub fn uses_scramble(&self) -> bool {
+        !matches!(self, Self::Relay | Self::Trainer)
+    }
+}
+
+impl std::fmt::Display for TimerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Standard => "Standard",
+                Self::Blindfolded => "Blindfolded",
+                Self::MultiBlind => "Multi-blind",
+                Self::FewestMoves => "Fewest moves",
+                Self::Relay => "Relay",
+                Self::Battle => "Two-player race",
+                Self::Competition => "Competition",
+                Self::Trainer => "Algorithm trainer",
+            }
+        )
+    }
 }
 
-pub enum State {
-    Idle { pressed: bool },
-    Ready,
-    Timing { last_tick: Instant },
-    Finished,
+/// A page of the application.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Page {
+    #[default]
+    Timer,
+    Goals,
+    History,
+    Preferences,
+    ThemeEditor,
+}
+
+impl Page {
+    pub const ALL: [Self; 5] = [
+        Self::Timer,
+        Self::Goals,
+        Self::History,
+        Self::Preferences,
+        Self::ThemeEditor,
+    ];
+}
+
+impl std::fmt::Display for Page {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Timer => "Timer",
+                Self::Goals => "Goals",
+                Self::History => "History",
+                Self::Preferences => "Preferences",
+                Self::ThemeEditor => "Theme editor",
+            }
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,16 +219,95 @@ pub enum ButtonType {
 
 #[derive(Debug, Clone)]
 pub enum Message {
+    KeyPressed(KeyCode),
+    KeyReleased(KeyCode),
     TriggerPress,
     TriggerRelease,
     TriggerTimeout,
     Tick(Instant),
+    PlayerPress(usize),
+    PlayerRelease(usize),
+    PlayerTimeout(usize),
 
     PenaltySelected(Option<data::Penalty>),
     ButtonPressed(ButtonType),
 
-    SolveSelected { index: usize },
+    SolveSelected {
+        index: usize,
+    },
+    SolveDetailClosed,
+    SolveCommentChanged(String),
+    SolveTagInputChanged(String),
+    SolveTagAdd,
+    SolveTagRemove(String),
+    TagFilterSelected(TagFilter),
+    SearchChanged(String),
+    PageSelected(Page),
+    FocusToggled,
+    ShortcutPressed(Shortcut),
+    ShortcutKeySelected(Shortcut, Key),
+    DialogClosed,
+    FocusFullscreenToggled(bool),
+    FocusAveragesToggled(bool),
+    PanesChanged(LayoutSize, PaneAction),
+    /// The panes were not resized since the given number of changes.
+    LayoutResizeEnded(u64),
+    LayoutPresetSelected(LayoutSize, LayoutPreset),
     ThemeSelected(tangible::Theme),
+    ThemesReload,
+    ThemeEditorNameChanged(String),
+    ThemeEditorColorChanged(tangible::theme::NamedColor, ColorField, String),
+    ThemeEditorColorPicked(tangible::theme::NamedColor, ColorField, palette::Hsl),
+    ThemeEditorApply,
+    ThemeEditorSave,
+    ThemeEditorExportPathChanged(String),
+    ThemeEditorExport,
+    ThemeEditorReset,
+    SystemThemeRead,
+    SystemThemeDetected(tangible::Theme),
+    InspectionSelected(Inspection),
+    HoldTimeSelected(HoldTime),
+    StartTriggerSelected(StartTrigger),
+    TriggerKeySelected(TriggerKey),
+    TimerDisplaySelected(TimerDisplay),
+    ReadyCueSelected(ReadyCue),
+    PrecisionSelected(Precision),
+    AveragesChanged(String),
+    AveragesSubmit,
+    PreferencesReset,
+    TimerModeSelected(TimerMode),
+    MultiBldSelected(data::MultiBld),
+    FmcStart,
+    FmcSolutionChanged(String),
+    FmcSubmit,
+    RelaySetSelected(data::RelaySet),
+    RelaySplitsToggled(bool),
+    NewSession,
+    RoundFormatSelected(data::RoundFormat),
+    CutoffSelected(Cutoff),
+    TimeLimitSelected(TimeLimit),
+    GoalStatisticSelected(data::GoalStatistic),
+    GoalTargetChanged(String),
+    GoalDeadlineChanged(String),
+    GoalAdd,
+    GoalRemove(usize),
+    GoalNotificationDismissed,
+    /// Put the last deleted solve back in the session.
+    SolveDeletionUndone,
+    DeletedSolveDismissed,
+    GhostSelected(GhostChoice),
+    ScrambleTypeSelected(data::scramble::ScrambleType),
+    ScrambleFileChanged(String),
+    ScrambleFileImport,
+    ScrambleFileLoaded {
+        path: String,
+        text: Result<String, String>,
+    },
+    ScrambleQueueExtra,
+    ScrambleQueueCleared,
+    CaseSetSelected(data::CaseSet),
+    CaseToggled(usize, bool),
+    RecognitionMistake,
 
     Todo,
 }
@@ -70,18 +326,75 @@ impl iced::Application for KTApplication {
     type Flags = ();
 
     fn new(_flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
-        (
-            Self {
-                preferences: Preferences::new(),
-
-                solve_time: data::SolveTime::default(),
-                link_to_last_solve: false,
-                last_pressed: Instant::now(),
-                state: State::Idle { pressed: false },
-                session: data::Session::new(),
+        let (custom_themes, theme_errors) = themes::load_custom_themes();
+        let preferences = Preferences::load(
+            &tangible::Theme::ALL
+                .iter()
+                .chain(custom_themes.iter())
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        let scramble_queue = data::ScrambleQueue::load();
+        let application = Self {
+            averages_input: preferences.averages.to_string(),
+            averages_error: None,
+            system_theme: match preferences.theme {
+                tangible::Theme::System => {
+                    system_theme::system_theme(&system_theme::SessionDesktop)
+                }
+                _ => tangible::Theme::Light,
             },
-            iced::Command::none(),
-        )
+            theme_editor: ThemeEditor::new(&preferences.theme),
+            panes: preferences.layout.panes(),
+            compact_panes: preferences.compact_layout.panes(),
+            layout_changes: 0,
+            dialog: None,
+            preferences,
+            custom_themes,
+            theme_errors,
+            page: Page::default(),
+
+            timer: Timer::new(),
+            link_to_last_solve: false,
+            session: data::Session::new(),
+            previous_sessions: Vec::new(),
+            selected_solve: None,
+            deleted_solve: None,
+            tag_input: String::new(),
+            tag_filter: TagFilter::default(),
+            search: String::new(),
+            search_query: None,
+            search_error: None,
+            filtered_solves: None,
+            ghost_choice: GhostChoice::None,
+            ghost: None,
+
+            mode: TimerMode::default(),
+            scramble: scramble_queue
+                .as_ref()
+                .and_then(|queue| queue.current())
+                .unwrap_or_else(|| data::scramble::scramble_cube(3)),
+            scramble_queue,
+            scramble_file: String::new(),
+            scramble_queue_error: None,
+            memo_time: None,
+            multi_bld: data::MultiBld::new(2, 2),
+            fmc_solution: String::new(),
+            relay_set: data::RelaySet::default(),
+            relay_scrambles: data::RelaySet::default().scramble(),
+            relay_marks: Vec::new(),
+            relay_splits: false,
+            battle: battle::Battle::new(),
+            round: data::Round::new(data::RoundFormat::default(), None, TimeLimit::default().0),
+            round_results: Vec::new(),
+            goals: Vec::new(),
+            goal_form: GoalForm::new(),
+            goal_notification: None,
+            trainer: data::Trainer::new(data::CaseSet::default()),
+        };
+        // Focus mode is kept from the last run, fullscreen included.
+        let command = window::change_mode(application.window_mode());
+        (application, command)
     }
 
     fn title(&self) -> String {
@@ -89,91 +402,549 @@ impl iced::Application for KTApplication {
     }
 
     fn theme(&self) -> Self::Theme {
-        self.preferences.theme
+        self.preferences
+            .theme
+            .clone()
+            .resolve(self.system_theme.clone())
     }
 
     fn update(&mut self, message: Self::Message) -> iced::Command<Self::Message> {
-        const PRESS_START_INTERVAL: Duration = Duration::from_millis(500);
-
         let command = match message {
+            Message::KeyPressed(key_code) => {
+                // The timer is not started from under a dialog.
+                if key_code == self.preferences.trigger_key.key_code() && self.dialog.is_none() {
+                    return self.update(Message::TriggerPress);
+                }
+                if let Some(command) = self.key_shortcut(key_code) {
+                    return command;
+                }
+                if key_code == KeyCode::Escape && self.in_focus() {
+                    return self.update(Message::FocusToggled);
+                }
+                Command::none()
+            }
+            Message::KeyReleased(key_code) => {
+                if key_code == self.preferences.trigger_key.key_code() {
+                    return self.update(Message::TriggerRelease);
+                }
+                Command::none()
+            }
             Message::TriggerPress => {
-                self.last_pressed = Instant::now();
-                match &mut self.state {
-                    State::Idle { pressed } => {
-                        let start_press = !*pressed;
-                        *pressed = true;
-                        if start_press {
-                            Command::perform(async_std::task::sleep(PRESS_START_INTERVAL), |()| {
-                                Message::TriggerTimeout
-                            })
+                let memo = self.mode == TimerMode::Blindfolded;
+                match self.timer.press(&self.preferences, memo) {
+                    Press::Hold(hold_time) => {
+                        return Command::perform(async_std::task::sleep(hold_time), |()| {
+                            Message::TriggerTimeout
+                        });
+                    }
+                    Press::Start => self.on_timer_started(),
+                    Press::EndMemo(memo_time) => self.memo_time = Some(memo_time),
+                    Press::Stop => {
+                        if self.mode == TimerMode::Relay
+                            && self.relay_splits
+                            && self.relay_marks.len() + 1 < self.relay_set.puzzles().len()
+                        {
+                            self.relay_marks.push(self.timer.solve_time.time);
                         } else {
-                            Command::none()
+                            self.finish_solve();
                         }
                     }
-                    State::Timing { last_tick: _ } => {
-                        self.session.add_solve(data::Solve {
-                            time: self.solve_time,
-                            timestamp: SystemTime::now(),
-                            scramble: "".to_string(),
-                        });
-                        self.link_to_last_solve = true;
-                        self.state = State::Finished;
-                        Command::none()
-                    }
-                    _ => Command::none(),
+                    Press::None => {}
                 }
+                Command::none()
             }
             Message::TriggerRelease => {
-                match &mut self.state {
-                    State::Idle { pressed } => {
-                        *pressed = false;
+                if self.timer.release(self.mode == TimerMode::Blindfolded) {
+                    self.on_timer_started();
+                }
+                Command::none()
+            }
+            Message::TriggerTimeout => {
+                self.timer.timeout(&self.preferences);
+                Command::none()
+            }
+            Message::Tick(now) => {
+                self.timer.tick(now);
+                for player in self.battle.players.iter_mut() {
+                    player.timer.tick(now);
+                }
+                if let (State::Timing { .. }, Some(time_limit)) =
+                    (&self.timer.state, self.time_limit())
+                {
+                    if self.timer.solve_time.time >= time_limit {
+                        // Attempts are stopped at the time limit.
+                        self.timer.solve_time.time = time_limit;
+                        self.finish_solve();
+                    }
+                }
+                Command::none()
+            }
+            Message::PlayerPress(player) => {
+                if self.mode == TimerMode::Battle && self.battle.can_start(player) {
+                    let timer = &mut self.battle.players[player].timer;
+                    match timer.press(&self.preferences, false) {
+                        Press::Hold(hold_time) => {
+                            return Command::perform(
+                                async_std::task::sleep(hold_time),
+                                move |()| Message::PlayerTimeout(player),
+                            );
+                        }
+                        Press::Stop => self.battle.finish(player),
+                        _ => {}
                     }
-                    State::Ready => {
-                        self.state = State::Timing {
-                            last_tick: Instant::now(),
-                        };
+                }
+                Command::none()
+            }
+            Message::PlayerRelease(player) => {
+                self.battle.players[player].timer.release(false);
+                Command::none()
+            }
+            Message::PlayerTimeout(player) => {
+                self.battle.players[player].timer.timeout(&self.preferences);
+                Command::none()
+            }
+            Message::PenaltySelected(penalty) => {
+                self.timer.solve_time.penalty = penalty;
+                if self.link_to_last_solve && self.mode == TimerMode::Competition {
+                    self.round.set_last_penalty(penalty);
+                    if let Some(s) = self.round.attempts().last() {
+                        self.timer.solve_time = s.time;
                     }
-                    State::Finished => {
-                        self.state = State::Idle { pressed: false };
+                } else if self.link_to_last_solve {
+                    if let Some(s) = self.session.last_solve_mut() {
+                        s.time = self.timer.solve_time;
+                        self.session.update_statistics_last();
+                        self.evaluate_goals();
+                        self.update_shown_solves();
                     }
-                    _ => {}
                 }
                 Command::none()
             }
-            Message::TriggerTimeout => {
-                match &mut self.state {
-                    State::Idle { pressed } => {
-                        if *pressed && Instant::now() - self.last_pressed > PRESS_START_INTERVAL {
-                            self.solve_time = data::SolveTime::default();
-                            self.state = State::Ready;
-                        }
+            Message::SolveSelected { index } => {
+                if !self.timer.state.is_running() {
+                    self.selected_solve = Some(index);
+                    self.tag_input.clear();
+                }
+                Command::none()
+            }
+            Message::SolveDetailClosed => {
+                self.selected_solve = None;
+                Command::none()
+            }
+            Message::SolveCommentChanged(comment) => {
+                if let Some(solve) = self
+                    .selected_solve
+                    .and_then(|index| self.session.get_solve_mut(index))
+                {
+                    solve.comment = comment;
+                    self.update_shown_solves();
+                }
+                Command::none()
+            }
+            Message::SolveTagInputChanged(tag) => {
+                self.tag_input = tag;
+                Command::none()
+            }
+            Message::SolveTagAdd => {
+                if let Some(solve) = self
+                    .selected_solve
+                    .and_then(|index| self.session.get_solve_mut(index))
+                {
+                    if solve.add_tag(&self.tag_input) {
+                        self.tag_input.clear();
+                        self.update_shown_solves();
                     }
-                    _ => {}
                 }
                 Command::none()
             }
-            Message::Tick(now) => {
-                match &mut self.state {
-                    State::Timing { last_tick } => {
-                        self.solve_time.time += now - *last_tick;
-                        *last_tick = now;
+            Message::SolveTagRemove(tag) => {
+                if let Some(solve) = self
+                    .selected_solve
+                    .and_then(|index| self.session.get_solve_mut(index))
+                {
+                    solve.remove_tag(&tag);
+                    self.update_shown_solves();
+                }
+                Command::none()
+            }
+            Message::TagFilterSelected(tag_filter) => {
+                self.tag_filter = tag_filter;
+                self.update_shown_solves();
+                Command::none()
+            }
+            Message::SearchChanged(search) => {
+                self.search = search;
+                self.update_shown_solves();
+                Command::none()
+            }
+            Message::PageSelected(page) => {
+                let was_in_focus = self.in_focus();
+                if !self.timer.state.is_running() {
+                    self.page = page;
+                }
+                self.update_window_mode(was_in_focus)
+            }
+            Message::GoalStatisticSelected(statistic) => {
+                self.goal_form.statistic = statistic;
+                Command::none()
+            }
+            Message::GoalTargetChanged(target) => {
+                self.goal_form.target = target;
+                Command::none()
+            }
+            Message::GoalDeadlineChanged(deadline) => {
+                self.goal_form.deadline = deadline;
+                Command::none()
+            }
+            Message::GoalAdd => {
+                match self.goal_form.parse() {
+                    Ok(goal) => {
+                        self.goals.push(goal);
+                        self.goal_form = GoalForm::new();
+                        self.evaluate_goals();
                     }
-                    _ => {}
+                    Err(error) => self.goal_form.error = Some(error),
                 }
                 Command::none()
             }
-            Message::PenaltySelected(penalty) => {
-                self.solve_time.penalty = penalty;
+            Message::GoalRemove(index) => {
+                if index < self.goals.len() {
+                    self.goals.remove(index);
+                }
+                Command::none()
+            }
+            Message::GoalNotificationDismissed => {
+                self.goal_notification = None;
+                Command::none()
+            }
+            Message::SolveDeletionUndone => {
+                self.undo_solve_deletion();
+                Command::none()
+            }
+            Message::DeletedSolveDismissed => {
+                self.deleted_solve = None;
+                Command::none()
+            }
+            Message::ThemeSelected(theme) => {
+                self.preferences.theme = theme.clone();
+                self.save_preferences();
+                match theme {
+                    tangible::Theme::System => self.update(Message::SystemThemeRead),
+                    _ => Command::none(),
+                }
+            }
+            Message::ThemesReload => {
+                self.reload_custom_themes();
+                Command::none()
+            }
+            Message::ThemeEditorNameChanged(name) => {
+                self.theme_editor.name = name;
+                Command::none()
+            }
+            Message::ThemeEditorColorChanged(name, field, input) => {
+                self.theme_editor.set_input(name, field, input);
+                Command::none()
+            }
+            Message::ThemeEditorColorPicked(name, field, hsl) => {
+                self.theme_editor.pick(name, field, hsl);
+                Command::none()
+            }
+            Message::ThemeEditorApply => {
+                self.apply_edited_theme();
+                Command::none()
+            }
+            Message::ThemeEditorSave => {
+                self.save_edited_theme();
+                Command::none()
+            }
+            Message::ThemeEditorExportPathChanged(path) => {
+                self.theme_editor.export_path = path;
+                Command::none()
+            }
+            Message::ThemeEditorExport => {
+                self.export_edited_theme();
+                Command::none()
+            }
+            Message::ThemeEditorReset => {
+                self.theme_editor = ThemeEditor::new(&self.theme());
+                Command::none()
+            }
+            Message::SystemThemeRead => Command::perform(
+                async_std::task::spawn_blocking(|| {
+                    system_theme::system_theme(&system_theme::SessionDesktop)
+                }),
+                Message::SystemThemeDetected,
+            ),
+            Message::SystemThemeDetected(theme) => {
+                self.system_theme = theme;
+                Command::none()
+            }
+            Message::InspectionSelected(inspection) => {
+                self.preferences.inspection = inspection;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::HoldTimeSelected(hold_time) => {
+                self.preferences.hold_time = hold_time;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::TriggerKeySelected(trigger_key) => {
+                self.preferences.trigger_key = trigger_key;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::TimerDisplaySelected(timer_display) => {
+                self.preferences.timer_display = timer_display;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::ReadyCueSelected(ready_cue) => {
+                self.preferences.ready_cue = ready_cue;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::PrecisionSelected(precision) => {
+                self.preferences.precision = precision;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::AveragesChanged(averages) => {
+                self.averages_input = averages;
+                Command::none()
+            }
+            Message::AveragesSubmit => {
+                match self.averages_input.parse::<Averages>() {
+                    Ok(averages) => {
+                        self.averages_input = averages.to_string();
+                        self.averages_error = None;
+                        self.preferences.averages = averages;
+                        self.save_preferences();
+                    }
+                    Err(e) => self.averages_error = Some(e.to_string()),
+                }
+                Command::none()
+            }
+            Message::PreferencesReset => {
+                self.preferences = Preferences::new();
+                self.panes = self.preferences.layout.panes();
+                self.compact_panes = self.preferences.compact_layout.panes();
+                self.averages_input = self.preferences.averages.to_string();
+                self.averages_error = None;
+                self.save_preferences();
+                window::change_mode(self.window_mode())
+            }
+            Message::FocusToggled => self.toggle_focus(),
+            Message::ShortcutPressed(shortcut) => self.run_shortcut(shortcut),
+            Message::ShortcutKeySelected(shortcut, key) => {
+                self.preferences.shortcuts.bind(shortcut, key);
+                self.save_preferences();
+                Command::none()
+            }
+            Message::DialogClosed => {
+                self.dialog = None;
+                Command::none()
+            }
+            Message::ButtonPressed(button) => {
+                let penalty = match button {
+                    ButtonType::SetOk => None,
+                    ButtonType::SetPlus2 => Some(data::Penalty::Plus2),
+                    ButtonType::SetDnf => Some(data::Penalty::Dnf),
+                    ButtonType::DeleteSolve { index } => {
+                        self.delete_solve(index);
+                        return Command::none();
+                    }
+                    ButtonType::SolveSummary { .. } => return Command::none(),
+                };
+                // Penalties are set on the last solve while its time is shown.
+                if self.link_to_last_solve && !self.timer.state.is_running() {
+                    return self.update(Message::PenaltySelected(penalty));
+                }
+                Command::none()
+            }
+            Message::FocusFullscreenToggled(fullscreen) => {
+                self.preferences.focus.fullscreen = fullscreen;
+                self.save_preferences();
+                window::change_mode(self.window_mode())
+            }
+            Message::FocusAveragesToggled(hide) => {
+                self.preferences.focus.hide_averages = hide;
+                self.save_preferences();
+                Command::none()
+            }
+            Message::PanesChanged(size, action) => self.update_panes(size, action),
+            Message::LayoutResizeEnded(changes) => {
+                self.end_layout_resize(changes);
+                Command::none()
+            }
+            Message::LayoutPresetSelected(size, preset) => {
+                self.apply_layout_preset(size, preset);
+                Command::none()
+            }
+            Message::TimerModeSelected(mode) => {
+                let was_in_focus = self.in_focus();
+                if !self.timer.state.is_running() && !self.battle.is_running() {
+                    self.mode = mode;
+                    self.link_to_last_solve = false;
+                    self.timer.state = State::Idle { pressed: false };
+                }
+                self.update_window_mode(was_in_focus)
+            }
+            Message::RelaySetSelected(relay_set) => {
+                if !self.timer.state.is_running() {
+                    self.relay_set = relay_set;
+                    self.relay_scrambles = relay_set.scramble();
+                }
+                Command::none()
+            }
+            Message::RelaySplitsToggled(relay_splits) => {
+                self.relay_splits = relay_splits;
+                Command::none()
+            }
+            Message::FmcStart => {
+                if !self.timer.state.is_running() {
+                    self.timer.solve_time = data::SolveTime::default();
+                    self.fmc_solution.clear();
+                    self.timer.start(false);
+                    self.on_timer_started();
+                }
+                Command::none()
+            }
+            Message::FmcSolutionChanged(solution) => {
+                self.fmc_solution = solution;
+                Command::none()
+            }
+            Message::FmcSubmit => {
+                if let State::Timing { .. } = self.timer.state {
+                    self.finish_solve();
+                }
+                Command::none()
+            }
+            Message::MultiBldSelected(multi_bld) => {
+                self.multi_bld = multi_bld;
                 if self.link_to_last_solve {
                     if let Some(s) = self.session.last_solve_mut() {
-                        s.time = self.solve_time;
+                        if let Some(bld) = &mut s.bld {
+                            bld.multi = Some(multi_bld);
+                        }
                         self.session.update_statistics_last();
+                        self.update_shown_solves();
                     }
                 }
                 Command::none()
             }
-            Message::ThemeSelected(theme) => {
-                self.preferences.theme = theme;
+            Message::RoundFormatSelected(format) => {
+                if self.can_change_round() {
+                    let (cutoff, time_limit) = (self.round.cutoff, self.round.time_limit);
+                    self.new_round(format, Cutoff(cutoff), TimeLimit(time_limit));
+                }
+                Command::none()
+            }
+            Message::CutoffSelected(cutoff) => {
+                if self.can_change_round() {
+                    let time_limit = TimeLimit(self.round.time_limit);
+                    self.new_round(self.round.format, cutoff, time_limit);
+                }
+                Command::none()
+            }
+            Message::TimeLimitSelected(time_limit) => {
+                if self.can_change_round() {
+                    let cutoff = Cutoff(self.round.cutoff);
+                    self.new_round(self.round.format, cutoff, time_limit);
+                }
+                Command::none()
+            }
+            Message::NewSession => {
+                if !self.timer.state.is_running() && self.session.get_n_solves() > 0 {
+                    let session = std::mem::take(&mut self.session);
+                    self.session.scramble_type = session.scramble_type;
+                    self.previous_sessions.push(session);
+                    self.link_to_last_solve = false;
+                    self.selected_solve = None;
+                    self.deleted_solve = None;
+                    self.tag_filter = TagFilter::default();
+                    self.update_shown_solves();
+                }
+                Command::none()
+            }
+            Message::GhostSelected(ghost_choice) => {
+                self.ghost = match ghost_choice {
+                    GhostChoice::None => None,
+                    GhostChoice::Session { index, .. } => self
+                        .previous_sessions
+                        .get(index)
+                        .cloned()
+                        .map(data::Ghost::new),
+                };
+                self.ghost_choice = ghost_choice;
+                Command::none()
+            }
+            Message::ScrambleTypeSelected(scramble_type) => {
+                if !self.timer.state.is_running() {
+                    self.session.scramble_type = scramble_type;
+                    if self.scramble_queue.is_none() {
+                        self.scramble = self.new_scramble();
+                    }
+                }
+                Command::none()
+            }
+            Message::ScrambleFileChanged(path) => {
+                self.scramble_file = path;
+                Command::none()
+            }
+            Message::ScrambleFileImport => {
+                let path = self.scramble_file.trim().to_string();
+                Command::perform(
+                    async move {
+                        let text = async_std::fs::read_to_string(&path)
+                            .await
+                            .map_err(|e| format!("cannot read {}: {}", path, e));
+                        (path, text)
+                    },
+                    |(path, text)| Message::ScrambleFileLoaded { path, text },
+                )
+            }
+            Message::ScrambleFileLoaded { path, text } => {
+                if !self.timer.state.is_running() {
+                    match text {
+                        Ok(text) => self.import_scrambles(&path, &text),
+                        Err(e) => self.scramble_queue_error = Some(e),
+                    }
+                }
+                Command::none()
+            }
+            Message::ScrambleQueueExtra => {
+                if !self.timer.state.is_running() {
+                    self.use_extra_scramble();
+                }
+                Command::none()
+            }
+            Message::ScrambleQueueCleared => {
+                if !self.timer.state.is_running() {
+                    self.clear_scramble_queue();
+                    self.scramble = self.new_scramble();
+                }
+                Command::none()
+            }
+            Message::CaseSetSelected(set) => {
+                if !self.timer.state.is_running() && set != self.trainer.set {
+                    self.trainer = data::Trainer::new(set);
+                }
+                Command::none()
+            }
+            Message::CaseToggled(index, selected) => {
+                if !self.timer.state.is_running() {
+                    self.trainer.set_selected(index, selected);
+                }
+                Command::none()
+            }
+            Message::RecognitionMistake => {
+                self.trainer.mark_mistake();
+                Command::none()
+            }
+            Message::StartTriggerSelected(start_trigger) => {
+                self.preferences.start_trigger = start_trigger;
+                self.save_preferences();
                 Command::none()
             }
             _ => Command::none(),
@@ -183,17 +954,47 @@ impl iced::Application for KTApplication {
     }
 
     fn subscription(&self) -> iced::Subscription<Self::Message> {
-        let kbs = subscription::events_with(|e, _s| {
+        let kbs = subscription::events_with(|e, s| {
+            // Keys typed in a text input are not for the timer.
+            if s == event::Status::Captured {
+                return None;
+            }
             if let Event::Keyboard(ke) = e {
+                // The trigger key is set in the preferences.
                 match ke {
+                    keyboard::Event::KeyPressed { key_code, .. } => {
+                        Some(Message::KeyPressed(key_code))
+                    }
+                    keyboard::Event::KeyReleased { key_code, .. } => {
+                        Some(Message::KeyReleased(key_code))
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        });
+
+        // Each player of a race has their own key.
+        let battle_kbs = subscription::events_with(|e, _s| {
+            if let Event::Keyboard(ke) = e {
+                match ke {
+                    keyboard::Event::KeyPressed {
+                        key_code: KeyCode::LControl,
+                        ..
+                    } => Some(Message::PlayerPress(0)),
                     keyboard::Event::KeyPressed {
-                        key_code: KeyCode::Space,
+                        key_code: KeyCode::RControl,
                         ..
-                    } => Some(Message::TriggerPress),
+                    } => Some(Message::PlayerPress(1)),
                     keyboard::Event::KeyReleased {
-                        key_code: KeyCode::Space,
+                        key_code: KeyCode::LControl,
                         ..
-                    } => Some(Message::TriggerRelease),
+                    } => Some(Message::PlayerRelease(0)),
+                    keyboard::Event::KeyReleased {
+                        key_code: KeyCode::RControl,
+                        ..
+                    } => Some(Message::PlayerRelease(1)),
                     _ => None,
                 }
             } else {
@@ -201,79 +1002,310 @@ impl iced::Application for KTApplication {
             }
         });
 
-        match self.state {
-            State::Timing { last_tick: _ } => Subscription::batch([
-                kbs,
-                time::every(Duration::from_millis(10)).map(Message::Tick),
-            ]),
+        // The keyboard is used to write the solution in fewest moves mode, and
+        // to fill in forms outside of the timer page.
+        let kbs = match self.mode {
+            _ if self.page != Page::Timer => Subscription::none(),
+            TimerMode::FewestMoves => Subscription::none(),
+            TimerMode::Battle => battle_kbs,
             _ => kbs,
+        };
+
+        let mut subscriptions = vec![kbs];
+        if self.timer.is_ticking() || self.battle.is_ticking() {
+            subscriptions.push(time::every(Duration::from_millis(10)).map(Message::Tick));
         }
+        if self.preferences.theme == tangible::Theme::System {
+            subscriptions.push(
+                iced::subscription::run(system_theme::watch_system_theme)
+                    .map(Message::SystemThemeDetected),
+            );
+        }
+        Subscription::batch(subscriptions)
     }
 
     fn view(&self) -> iced::Element<'_, Self::Message, iced::Renderer<Self::Theme>> {
         iced_lazy::responsive(move |size| {
             let compact = size.width <= 600.0;
 
-            let content = if compact {
-                let mut column = widget::Column::new();
-                column = column.push(self.center_timer());
-                if !matches!(self.state, State::Ready | State::Timing { .. }) {
-                    column = column
-                        .push(widget::horizontal_rule(1))
-                        .push(self.bottombar());
-                }
-
-                iced::Element::from(column)
+            let content = if self.page == Page::Goals {
+                self.goals_view()
+            } else if self.page == Page::History {
+                self.history_view()
+            } else if self.page == Page::Preferences {
+                self.preferences_view()
+            } else if self.page == Page::ThemeEditor {
+                self.theme_editor_view()
+            } else if self.mode == TimerMode::Battle {
+                self.battle_view()
+            } else if self.in_focus() {
+                self.focus_view()
+            } else if self.timer.state.is_running() {
+                // Only the timer is shown while timing.
+                self.center_panel()
+            } else if compact {
+                self.panes_view(LayoutSize::Compact)
             } else {
-                let mut row = widget::Row::new();
-                if !matches!(self.state, State::Ready | State::Timing { .. }) {
-                    row = row.push(self.sidebar()).push(widget::vertical_rule(1));
-                }
-                row = row.push(self.center_timer());
-
-                iced::Element::from(row)
+                self.panes_view(LayoutSize::Regular)
             };
 
+            let theme = self.theme();
+            let mut toasts: Vec<_> = self
+                .goal_notification
+                .iter()
+                .map(|notification| {
+                    tangible::widget::toast(
+                        notification,
+                        theme.palette().success,
+                        Message::GoalNotificationDismissed,
+                    )
+                })
+                .collect();
+            if let Some((_, solve)) = &self.deleted_solve {
+                toasts.push(tangible::widget::action_toast(
+                    format!("Deleted solve {}", solve),
+                    theme.palette().neutral,
+                    "Undo",
+                    Message::SolveDeletionUndone,
+                    Message::DeletedSolveDismissed,
+                ));
+            }
+            let content = tangible::widget::Toasts::new(content, toasts);
+
             // let mut winbox = widget::Column::new();
-            // if !matches!(self.state, State::Ready | State::Timing { .. }) {
+            // if !matches!(self.timer.state, State::Ready | State::Timing { .. }) {
             // }
             // winbox = winbox.push(content);
             // winbox.into()
-            widget::column![
-                self.headerbar(),
-                content,
-            ]
-            .into()
+            let content: iced::Element<'_, Message, iced::Renderer<tangible::Theme>> =
+                if self.in_focus() {
+                    content.into()
+                } else {
+                    widget::column![self.headerbar(), content].into()
+                };
+            match self.dialog {
+                Some(dialog) => tangible::widget::Modal::new(content, self.dialog_view(dialog))
+                    .on_dismiss(Message::DialogClosed)
+                    .into(),
+                None => content,
+            }
         })
         .into()
     }
 }
 
 impl KTApplication {
+    /// Reset the attempt data of the current mode after the timer started.
+    fn on_timer_started(&mut self) {
+        self.memo_time = None;
+        self.relay_marks.clear();
+        self.multi_bld.solved = self.multi_bld.attempted;
+        if self.mode == TimerMode::Competition && self.round.is_finished() {
+            self.new_round(
+                self.round.format,
+                Cutoff(self.round.cutoff),
+                TimeLimit(self.round.time_limit),
+            );
+        }
+    }
+
+    /// Remove a solve from the session.
+    ///
+    /// The solve is kept until another one is deleted, so that the deletion can be undone.
+    fn delete_solve(&mut self, index: usize) {
+        if self.timer.state.is_running() {
+            return;
+        }
+        let Some(solve) = self.session.remove_solve(index) else {
+            return;
+        };
+        self.deleted_solve = Some((index, solve));
+        if index == self.session.get_n_solves() {
+            // The time shown is no longer the last solve.
+            self.link_to_last_solve = false;
+        }
+        self.selected_solve = match self.selected_solve {
+            Some(selected) if selected == index => None,
+            Some(selected) if selected > index => Some(selected - 1),
+            selected => selected,
+        };
+        self.evaluate_goals();
+        self.update_shown_solves();
+    }
+
+    /// Put the last deleted solve back where it was in the session.
+    fn undo_solve_deletion(&mut self) {
+        if self.timer.state.is_running() {
+            return;
+        }
+        let Some((index, solve)) = self.deleted_solve.take() else {
+            return;
+        };
+        self.session.insert_solve(index, solve);
+        self.selected_solve = self.selected_solve.map(|selected| {
+            if selected >= index {
+                selected + 1
+            } else {
+                selected
+            }
+        });
+        self.evaluate_goals();
+        self.update_shown_solves();
+    }
+
+    /// Generate a scramble of the type of the session.
+    fn new_scramble(&self) -> data::Algorithm {
+        self.session.scramble_type.scramble(&mut rand::thread_rng())
+    }
+
+    /// Get the time limit of an attempt in the current mode, if any.
+    fn time_limit(&self) -> Option<Duration> {
+        match self.mode {
+            TimerMode::MultiBlind => Some(self.multi_bld.time_limit()),
+            TimerMode::FewestMoves => Some(FMC_TIME_LIMIT),
+            TimerMode::Competition => self.round.time_limit,
+            _ => None,
+        }
+    }
+
+    /// Stop the timer and record the solve in the session, or in the round in competition mode
+    /// and in the trainer in trainer mode.
+    fn finish_solve(&mut self) {
+        let relay = match self.mode {
+            TimerMode::Relay => Some(data::Relay::new(
+                self.relay_set,
+                std::mem::replace(&mut self.relay_scrambles, self.relay_set.scramble()),
+                &self.relay_marks,
+                self.timer.solve_time.time,
+            )),
+            _ => None,
+        };
+        let scramble = match (&relay, self.mode) {
+            (Some(relay), _) => relay.scramble_lines(),
+            (None, TimerMode::Trainer) => self.trainer.scramble.to_string(),
+            (None, _) => self.scramble.to_string(),
+        };
+        let bld = match self.mode {
+            TimerMode::Standard
+            | TimerMode::FewestMoves
+            | TimerMode::Relay
+            | TimerMode::Battle
+            | TimerMode::Competition
+            | TimerMode::Trainer => None,
+            TimerMode::Blindfolded => Some(data::Bld {
+                memo: self.memo_time,
+                multi: None,
+            }),
+            TimerMode::MultiBlind => Some(data::Bld {
+                memo: None,
+                multi: Some(self.multi_bld),
+            }),
+        };
+
+        let fmc = match self.mode {
+            TimerMode::FewestMoves => Some(data::Fmc::new(&scramble, &self.fmc_solution)),
+            _ => None,
+        };
+
+        let solve = data::Solve {
+            bld,
+            fmc,
+            relay,
+            ..data::Solve::new(self.timer.solve_time, scramble)
+        };
+        match self.mode {
+            TimerMode::Competition => {
+                self.round.add_attempt(solve);
+                // Show the time with the penalty of the time limit, if any.
+                if let Some(s) = self.round.attempts().last() {
+                    self.timer.solve_time = s.time;
+                }
+            }
+            TimerMode::Trainer => self.trainer.record(solve.time),
+            _ => {
+                self.session.add_solve(solve);
+                self.evaluate_goals();
+                self.update_shown_solves();
+            }
+        }
+        if self.mode.uses_scramble() {
+            self.scramble = self.next_scramble();
+        }
+        // Penalties of trained cases are not editable.
+        self.link_to_last_solve = self.mode != TimerMode::Trainer;
+        match self.mode {
+            // There is no trigger release to wait for in fewest moves mode.
+            TimerMode::FewestMoves => self.timer.state = State::Idle { pressed: false },
+            _ => self.timer.stop(),
+        }
+    }
+
     fn headerbar(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
-        widget::column![
-            widget::row![
-                widget::pick_list(
-                    &tangible::Theme::ALL[..],
-                    Some(self.preferences.theme),
-                    Message::ThemeSelected,
-                )
+        let start = widget::row![
+            widget::pick_list(&Page::ALL[..], Some(self.page), Message::PageSelected)
                 .padding([4, 8])
-                .width(Length::Shrink)
-                .placeholder("--"),
-            ]
-            .spacing(4)
-            .padding(4),
-            widget::horizontal_rule(1),
+                .width(Length::Shrink),
+            widget::pick_list(
+                &TimerMode::ALL[..],
+                Some(self.mode),
+                Message::TimerModeSelected
+            )
+            .padding([4, 8])
+            .width(Length::Shrink),
         ]
-        .into()
+        .spacing(4)
+        .align_items(Alignment::Center);
+
+        let mut end = widget::row![].spacing(4).align_items(Alignment::Center);
+        if let Some(indicator) = self.scramble_queue_indicator() {
+            end = end.push(indicator);
+        }
+        if self.can_focus() {
+            end = end.push(
+                widget::button("Shortcuts")
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Flat)
+                    .on_press(Message::ShortcutPressed(Shortcut::CheatSheet)),
+            );
+            end = end.push(
+                widget::button("Focus")
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Flat)
+                    .on_press(Message::FocusToggled),
+            );
+        }
+        end = end.push(
+            widget::pick_list(
+                self.themes(),
+                Some(self.preferences.theme.clone()),
+                Message::ThemeSelected,
+            )
+            .padding([4, 8])
+            .width(Length::Shrink)
+            .placeholder("--"),
+        );
+
+        tangible::widget::headerbar(start, end)
+    }
+
+    /// The timer, or the details of the selected solve.
+    fn center_panel(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        match self.selected_solve {
+            Some(index) if !self.timer.state.is_running() => self.solve_detail(index),
+            _ => self.center_timer(),
+        }
     }
 
     fn center_timer(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        if self.mode == TimerMode::FewestMoves {
+            return self.fmc_timer();
+        }
+
         iced_lazy::responsive(move |size| {
             let compact = size.width <= 450.0;
             let duration_text_font_size = if compact
                 || self
+                    .timer
                     .solve_time
                     .recorded_time()
                     .map(|t| t.as_secs() > 600)
@@ -284,81 +1316,85 @@ impl KTApplication {
                 120.0
             };
 
-            let theme = self.theme();
-            let palette = theme.palette();
-            let duration_text_color = match self.state {
-                State::Idle { pressed: true } | State::Finished => palette.destructive.base,
-                State::Ready => palette.success.base,
-                _ => palette.view.fg,
-            };
-
-            let duration_text = if let Some(duration) = self.solve_time.recorded_time() {
-                let seconds = duration.as_secs();
-                widget::row![
-                    widget::text(if seconds >= 60 {
-                        format!("{:0>1}:{:0>2}", seconds / 60, seconds % 60,)
-                    } else {
-                        format!("{:0>1}", seconds,)
-                    })
-                    .style(duration_text_color)
-                    .size(duration_text_font_size),
-                    widget::text(format!(
-                        ".{:0>2}{}",
-                        duration.subsec_millis() / 10,
-                        if self.solve_time.is_plus2() { "+" } else { "" }
-                    ))
-                    .style(duration_text_color)
-                    .size(duration_text_font_size * 0.75),
-                ]
-                .align_items(Alignment::End)
-            } else {
-                widget::row![widget::text("DNF")
-                    .style(duration_text_color)
-                    .size(duration_text_font_size),]
-            };
+            let duration_text = self.timer_text(&self.timer, duration_text_font_size);
 
             let center_content = {
-                let mut center_content = widget::column![duration_text,]
-                    .spacing(16)
-                    .align_items(Alignment::Center);
+                let mut center_content =
+                    widget::column![].spacing(16).align_items(Alignment::Center);
+
+                if !self.timer.state.is_running() && self.mode != TimerMode::MultiBlind {
+                    center_content = center_content.push(self.scramble_text());
+                }
+                center_content = center_content.push(duration_text);
+
+                if let Some(progress_bar) = self.hold_progress_bar(&self.timer) {
+                    center_content = center_content.push(progress_bar);
+                }
+
+                if let Some(ready_label) = self.ready_label() {
+                    center_content = center_content.push(ready_label);
+                }
+
+                if let State::Memo { .. } = self.timer.state {
+                    center_content = center_content
+                        .push(widget::text("Memorizing").style(tangible::theme::Text::Dim));
+                }
+
+                if let Some(ghost_delta) = self.ghost_delta() {
+                    center_content = center_content.push(ghost_delta);
+                }
 
-                if !matches!(self.state, State::Ready | State::Timing { .. }) {
+                if let (State::Timing { .. }, TimerMode::Relay, true) =
+                    (&self.timer.state, self.mode, self.relay_splits)
+                {
+                    let puzzles = self.relay_set.puzzles();
+                    let index = self.relay_marks.len().min(puzzles.len() - 1);
+                    center_content = center_content.push(
+                        widget::text(format!(
+                            "{0}x{0}x{0} ({1}/{2})",
+                            puzzles[index],
+                            index + 1,
+                            puzzles.len()
+                        ))
+                        .style(tangible::theme::Text::Dim),
+                    );
+                }
+
+                if !self.timer.state.is_running() {
                     if self.link_to_last_solve {
                         center_content = center_content.push(self.penalty_selector());
+                        if let Some(bld_summary) = self.bld_summary() {
+                            center_content = center_content.push(bld_summary);
+                        }
+                        if let Some(relay_summary) = self.relay_summary() {
+                            center_content = center_content.push(relay_summary);
+                        }
+                    }
+                    if self.mode == TimerMode::Relay {
+                        center_content = center_content.push(self.relay_options());
+                    }
+                    if self.mode == TimerMode::MultiBlind {
+                        center_content = center_content.push(self.multi_bld_selector());
+                    }
+                    if self.mode == TimerMode::Competition {
+                        center_content = center_content
+                            .push(self.round_summary())
+                            .push(self.competition_options());
+                    } else if self.mode == TimerMode::Trainer {
+                        if let Some(trainer_summary) = self.trainer_summary() {
+                            center_content = center_content.push(trainer_summary);
+                        }
+                        center_content = center_content.push(self.trainer_options());
+                    } else {
+                        center_content = center_content.push(self.session_stats());
+                        if let Some(ghost_summary) = self.ghost_summary() {
+                            center_content = center_content.push(ghost_summary);
+                        }
+                        center_content = center_content.push(self.session_options());
+                    }
+                    if self.mode != TimerMode::Trainer {
+                        center_content = center_content.push(self.scramble_queue_options());
                     }
-                    center_content = center_content.push(
-                        widget::column![
-                            widget::row![
-                                widget::text("Ao5")
-                                    .width(Length::FillPortion(1))
-                                    .horizontal_alignment(alignment::Horizontal::Right),
-                                widget::text(
-                                    self.session
-                                        .last_ao5()
-                                        .map_or("--".to_string(), |s| s.to_string()),
-                                )
-                                .width(Length::FillPortion(1))
-                                .horizontal_alignment(alignment::Horizontal::Left),
-                            ]
-                            .spacing(8)
-                            .width(Length::Fixed(200.0)),
-                            widget::row![
-                                widget::text("Ao12")
-                                    .width(Length::FillPortion(1))
-                                    .horizontal_alignment(alignment::Horizontal::Right),
-                                widget::text(
-                                    self.session
-                                        .last_ao12()
-                                        .map_or("--".to_string(), |s| s.to_string()),
-                                )
-                                .width(Length::FillPortion(1))
-                                .horizontal_alignment(alignment::Horizontal::Left),
-                            ]
-                            .spacing(8)
-                            .width(Length::Fixed(200.0)),
-                        ]
-                        .spacing(8),
-                    )
                 }
 
                 // center_content.push(
@@ -384,58 +1420,354 @@ impl KTApplication {
         .into()
     }
 
-    fn penalty_button(
+    /// A "Hold" or "Ready" label, if the ready cue has labels.
+    fn ready_label(&self) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        if self.preferences.ready_cue != ReadyCue::Label {
+            return None;
+        }
+        let label = match self.timer.state {
+            State::Idle { pressed: true } | State::Inspection { holding: true } => "Hold",
+            State::Ready => "Ready",
+            _ => return None,
+        };
+        Some(widget::text(label).size(24).into())
+    }
+
+    /// Running time of a timer, colored by its state.
+    fn timer_text(
         &self,
-        label: &str,
-        penalty: Option<data::Penalty>,
-    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
-        let label = widget::text(label).horizontal_alignment(alignment::Horizontal::Center);
+        timer: &Timer,
+        duration_text_font_size: f32,
+    ) -> widget::Row<'_, Message, iced::Renderer<tangible::Theme>> {
+        let theme = self.theme();
+        let palette = theme.palette();
+        let (hold_color, ready_color) = self.preferences.ready_cue.colors(palette);
+        let duration_text_color = match timer.state {
+            State::Idle { pressed: true }
+            | State::Inspection { holding: true }
+            | State::Finished => hold_color,
+            State::Ready => ready_color,
+            _ => palette.view.fg,
+        };
 
-        let style = if self.solve_time.penalty == penalty {
-            self.theme().palette().selector_active.into()
-        } else {
-            // tangible::theme::Button::Flat
-            self.theme().palette().selector.into()
+        if let Some(inspection_time) = timer.inspection_time() {
+            // Count down the inspection, then show the penalty the solve would get.
+            let countdown = match timer::INSPECTION_TIME.checked_sub(inspection_time) {
+                Some(remaining) => (remaining.as_secs() + 1).to_string(),
+                None if inspection_time <= timer::INSPECTION_TIME + Duration::from_secs(2) => {
+                    "+2".to_string()
+                }
+                None => "DNF".to_string(),
+            };
+            return widget::row![widget::text(countdown)
+                .style(duration_text_color)
+                .size(duration_text_font_size)];
+        }
+
+        let timer_display = match timer.state {
+            State::Memo { .. } | State::Timing { .. } => self.preferences.timer_display,
+            _ => TimerDisplay::Full,
+        };
+
+        match (timer_display, timer.solve_time.recorded_time()) {
+            (TimerDisplay::Hidden, _) => widget::row![widget::text("")
+                .style(duration_text_color)
+                .size(duration_text_font_size)],
+            (TimerDisplay::Label, _) => widget::row![widget::text("Solving")
+                .style(duration_text_color)
+                .size(duration_text_font_size * 0.75)],
+            (TimerDisplay::Seconds, Some(duration)) => {
+                widget::row![widget::text(display_whole_seconds(&duration))
+                    .style(duration_text_color)
+                    .size(duration_text_font_size)]
+            }
+            (TimerDisplay::Full, Some(duration)) => tangible::widget::time_display(
+                display_whole_seconds(&duration),
+                format!(
+                    "{}{}",
+                    self.preferences.precision.format_fraction(&duration),
+                    if timer.solve_time.is_plus2() { "+" } else { "" }
+                ),
+                duration_text_font_size,
+                duration_text_color,
+            ),
+            (_, None) => widget::row![widget::text("DNF")
+                .style(duration_text_color)
+                .size(duration_text_font_size),],
+        }
+    }
+
+    /// Progress bar of the hold before a timer is ready, if holding.
+    fn hold_progress_bar(
+        &self,
+        timer: &Timer,
+    ) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        let progress = timer.hold_progress(&self.preferences)?;
+        let style = match (self.preferences.ready_cue, progress >= 1.0) {
+            (ReadyCue::OrangeBlue, true) => tangible::theme::ProgressBar::Accent,
+            (ReadyCue::OrangeBlue, false) => tangible::theme::ProgressBar::Warning,
+            (_, true) => tangible::theme::ProgressBar::Success,
+            (_, false) => tangible::theme::ProgressBar::Accent,
         };
 
-        widget::button(label)
-            .style(style)
-            .padding(4)
-            .on_press(Message::PenaltySelected(penalty))
-            .width(Length::FillPortion(1))
+        Some(
+            widget::progress_bar(0.0..=1.0, progress)
+                .style(style)
+                .width(Length::Fixed(200.0))
+                .height(Length::Fixed(4.0))
+                .into(),
+        )
+    }
+
+    /// Statistics of the session shown below the timer.
+    ///
+    /// The statistics are computed over the solves with the tag filtered by, if any.
+    fn session_stats(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let (session, _) = self.shown_solves();
+        let mut stats = widget::Column::with_children(
+            self.preferences
+                .averages
+                .sizes()
+                .iter()
+                .map(|&n| {
+                    stat_row(
+                        &format!("Ao{}", n),
+                        session
+                            .last_average_of(n)
+                            .map_or("--".to_string(), |s| s.to_string()),
+                    )
+                })
+                .collect(),
+        )
+        .spacing(8);
+        if let Some(tag) = &self.tag_filter.0 {
+            stats = stats.push(
+                widget::text(format!(
+                    "{} solves tagged \"{}\"",
+                    session.get_n_solves(),
+                    tag
+                ))
+                .style(tangible::theme::Text::Dim),
+            );
+        }
+        if self.mode == TimerMode::MultiBlind {
+            stats = stats.push(stat_row(
+                "Best",
+                session
+                    .best_multi_bld()
+                    .map_or("--".to_string(), |r| r.to_string()),
+            ));
+        }
+
+        stats.into()
+    }
+
+    fn scramble_text(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        if self.mode == TimerMode::Relay {
+            return widget::Column::with_children(
+                self.relay_set
+                    .puzzles()
+                    .iter()
+                    .zip(self.relay_scrambles.iter())
+                    .map(|(n, scramble)| {
+                        widget::text(format!("{0}x{0}x{0}: {1}", n, scramble))
+                            .size(16.0)
+                            .into()
+                    })
+                    .collect(),
+            )
+            .spacing(4)
+            .padding([0, 16])
+            .into();
+        }
+
+        let scramble = match self.mode {
+            TimerMode::Trainer => &self.trainer.scramble,
+            _ => &self.scramble,
+        };
+        widget::text(scramble)
+            .size(20.0)
+            .horizontal_alignment(alignment::Horizontal::Center)
             .into()
     }
 
-    fn penalty_selector(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
-        let row = widget::row![
-            self.penalty_button("OK", None),
-            self.penalty_button("+2", Some(data::Penalty::Plus2)),
-            self.penalty_button("DNF", Some(data::Penalty::Dnf)),
+    fn relay_options(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        widget::row![
+            widget::pick_list(
+                &data::RelaySet::ALL[..],
+                Some(self.relay_set),
+                Message::RelaySetSelected,
+            )
+            .padding([4, 8]),
+            widget::checkbox(
+                "Record splits",
+                self.relay_splits,
+                Message::RelaySplitsToggled
+            ),
         ]
-        .spacing(4)
+        .spacing(16)
         .align_items(Alignment::Center)
-        .width(Length::Fixed(200.0));
-
-        widget::container(row)
-            // .style(tangible::theme::Container::Solid(
-            //     // tangible::theme::NamedColor::Neutral,
-            //     self.theme().palette().selector.into()
-            // ))
-            .style(self.theme().palette().selector)
-            .padding(4)
+        .into()
+    }
+
+    /// Splits of the last solve, if it is a relay solve with recorded splits.
+    fn relay_summary(&self) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        let relay = self.session.last_solve()?.relay.as_ref()?;
+        let splits = relay
+            .legs
+            .iter()
+            .map(|leg| {
+                leg.split.map(|split| {
+                    format!(
+                        "{} {}",
+                        leg.puzzle_name(),
+                        data::SolveTime::new(split, None)
+                    )
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(
+            widget::text(splits.join("  "))
+                .style(tangible::theme::Text::Dim)
+                .into(),
+        )
+    }
+
+    /// Timer of a fewest moves attempt, counting down from an hour.
+    fn fmc_timer(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let theme = self.theme();
+        let palette = theme.palette();
+        let mut content = widget::column![].spacing(16).align_items(Alignment::Center);
+
+        if let State::Timing { .. } = self.timer.state {
+            let remaining = FMC_TIME_LIMIT.saturating_sub(self.timer.solve_time.time);
+            let countdown_color = if remaining < Duration::from_secs(300) {
+                palette.destructive.base
+            } else {
+                palette.view.fg
+            };
+
+            let status =
+                match data::Fmc::check_solution(&self.scramble.to_string(), &self.fmc_solution) {
+                    Ok(moves) => format!("{} moves, solves the scramble", moves),
+                    Err(e) => e.to_string(),
+                };
+
+            content = content
+                .push(self.scramble_text())
+                .push(
+                    widget::text(display_whole_seconds(&remaining))
+                        .style(countdown_color)
+                        .size(80.0),
+                )
+                .push(
+                    widget::text_input("Solution", &self.fmc_solution)
+                        .on_input(Message::FmcSolutionChanged)
+                        .on_submit(Message::FmcSubmit)
+                        .padding(8)
+                        .width(Length::Fixed(400.0)),
+                )
+                .push(widget::text(status).style(tangible::theme::Text::Dim))
+                .push(
+                    widget::button("Submit")
+                        .style(tangible::theme::Button::Suggested)
+                        .padding([4, 16])
+                        .on_press(Message::FmcSubmit),
+                );
+        } else {
+            if let Some(solve) = self.session.last_solve().filter(|s| s.is_fmc()) {
+                content = content.push(widget::text(solve).size(120.0)).push(stat_row(
+                    "Mo3",
+                    self.session
+                        .last_mo3_moves()
+                        .map_or("--".to_string(), |m| m.to_string()),
+                ));
+            }
+            content = content.push(
+                widget::button("Start")
+                    .style(tangible::theme::Button::Suggested)
+                    .padding([4, 16])
+                    .on_press(Message::FmcStart),
+            );
+        }
+
+        widget::container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
             .into()
     }
 
+    /// Memo and execution split of the last solve, if it is a blindfolded solve.
+    fn bld_summary(&self) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        let solve = self.session.last_solve()?;
+        let memo = solve.memo_time()?;
+        let execution = solve.execution_time()?;
+
+        Some(
+            widget::text(format!(
+                "Memo {}  Exec {}",
+                data::SolveTime::new(memo, None),
+                data::SolveTime::new(execution, None),
+            ))
+            .style(tangible::theme::Text::Dim)
+            .into(),
+        )
+    }
+
+    fn multi_bld_selector(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let multi_bld = self.multi_bld;
+        let solved: Vec<u32> = (0..=multi_bld.attempted).collect();
+        let attempted: Vec<u32> = (2..=MAX_MULTI_BLD_PUZZLES).collect();
+
+        widget::row![
+            widget::pick_list(solved, Some(multi_bld.solved), move |solved| {
+                Message::MultiBldSelected(data::MultiBld::new(solved, multi_bld.attempted))
+            })
+            .padding([4, 8]),
+            widget::text("/"),
+            widget::pick_list(attempted, Some(multi_bld.attempted), move |attempted| {
+                Message::MultiBldSelected(data::MultiBld::new(multi_bld.solved, attempted))
+            })
+            .padding([4, 8]),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    fn penalty_selector(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        tangible::widget::segmented(
+            [
+                ("OK", None),
+                ("+2", Some(data::Penalty::Plus2)),
+                ("DNF", Some(data::Penalty::Dnf)),
+            ],
+            &self.timer.solve_time.penalty,
+            Message::PenaltySelected,
+            self.theme().palette(),
+        )
+        .width(Length::Fixed(208.0))
+        .into()
+    }
+
     fn sidebar(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
-        if self.session.get_n_solves() > 0 {
+        let (session, indices) = self.shown_solves();
+        let sidebar: iced::Element<'_, Message, iced::Renderer<tangible::Theme>> = if session
+            .get_n_solves()
+            > 0
+        {
             let times_column = widget::Column::with_children(
-                self.session
+                session
                     .iter()
                     .enumerate()
                     .rev()
                     .map(|(i, se)| {
+                        let index = indices.as_ref().map_or(i, |indices| indices[i]);
                         let row = widget::row![
-                            widget::text(se.solve.time)
+                            widget::text(&se.solve)
                                 .horizontal_alignment(alignment::Horizontal::Center)
                                 .width(Length::FillPortion(1)),
                             widget::text(se.ao5.map_or("--".to_string(), |ao5| ao5.to_string()))
@@ -450,7 +1782,7 @@ impl KTApplication {
                         widget::button(row)
                             .padding([4, 8])
                             .style(tangible::theme::Button::Flat)
-                            .on_press(Message::SolveSelected { index: i })
+                            .on_press(Message::SolveSelected { index })
                             .into()
                     })
                     .collect(),
@@ -458,7 +1790,7 @@ impl KTApplication {
             .spacing(4)
             .padding(8)
             .align_items(Alignment::Start)
-            .width(Length::Fixed(300.0));
+            .width(Length::Fill);
 
             widget::scrollable(times_column)
                 .vertical_scroll(
@@ -477,39 +1809,29 @@ impl KTApplication {
             .align_items(Alignment::Center);
 
             widget::container(content)
-                .width(Length::Fixed(300.0))
+                .width(Length::Fill)
                 .height(Length::Fill)
                 .center_x()
                 .center_y()
                 .into()
-        }
+        };
+
+        widget::column![self.sidebar_header(), sidebar].into()
     }
+}
 
-    fn bottombar(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
-        let times_row = widget::Row::with_children(
-            self.session
-                .iter()
-                .enumerate()
-                .map(|(i, se)| {
-                    widget::button(widget::text(se.solve.time))
-                        .padding([4, 8])
-                        .style(tangible::theme::Button::Flat)
-                        .on_press(Message::SolveSelected { index: i })
-                        .into()
-                })
-                .collect(),
-        )
-        .spacing(4)
-        .padding(4)
-        .align_items(Alignment::Start);
-
-        widget::scrollable(times_row)
-            .horizontal_scroll(
-                widget::scrollable::Properties::new()
-                    .width(4.0)
-                    .margin(4.0)
-                    .scroller_width(4.0),
-            )
-            .into()
+/// Time limit of a fewest moves attempt.
+const FMC_TIME_LIMIT: Duration = Duration::from_secs(3600);
+
+/// Largest number of puzzles selectable for a multi-blind attempt.
+const MAX_MULTI_BLD_PUZZLES: u32 = 60;
+
+/// Format the whole seconds of a duration, with minutes if needed.
+fn display_whole_seconds(duration: &Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 60 {
+        format!("{:0>1}:{:0>2}", seconds / 60, seconds % 60,)
+    } else {
+        format!("{:0>1}", seconds,)
     }
 }
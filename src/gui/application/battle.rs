@@ -0,0 +1,229 @@
+use iced::{alignment, widget, Alignment, Length};
+
+use crate::tangible::pattern::stat_row;
+use crate::{data, tangible};
+
+use super::{KTApplication, Message, State, Timer};
+
+/// A contestant of a two-player race.
+pub struct Player {
+    pub name: &'static str,
+    /// Name of the key bound to the player's timer.
+    pub key_name: &'static str,
+    pub timer: Timer,
+    pub session: data::Session,
+    /// Time of the current round, once the player has finished it.
+    pub round_time: Option<data::SolveTime>,
+    pub wins: u32,
+}
+
+impl Player {
+    fn new(name: &'static str, key_name: &'static str) -> Self {
+        Self {
+            name,
+            key_name,
+            timer: Timer::new(),
+            session: data::Session::new(),
+            round_time: None,
+            wins: 0,
+        }
+    }
+}
+
+/// Outcome of a round of a race.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundOutcome {
+    /// The player with the given index won.
+    Win(usize),
+    Draw,
+}
+
+/// Two players racing on the same scrambles, each with their own timer and session.
+pub struct Battle {
+    pub players: [Player; 2],
+    pub scramble: data::Algorithm,
+    pub draws: u32,
+    pub last_outcome: Option<RoundOutcome>,
+}
+
+impl Battle {
+    pub fn new() -> Self {
+        Self {
+            players: [
+                Player::new("Player 1", "Left Ctrl"),
+                Player::new("Player 2", "Right Ctrl"),
+            ],
+            scramble: data::scramble::scramble_cube(3),
+            draws: 0,
+            last_outcome: None,
+        }
+    }
+
+    /// Return `true` if any of the timers is ready or running.
+    pub fn is_running(&self) -> bool {
+        self.players.iter().any(|p| p.timer.state.is_running())
+    }
+
+    /// Return `true` if any of the timers needs to be ticked.
+    pub fn is_ticking(&self) -> bool {
+        self.players.iter().any(|p| p.timer.is_ticking())
+    }
+
+    /// Return `true` if the player can start a solve, that is if they have not finished
+    /// the current round yet.
+    pub fn can_start(&self, player: usize) -> bool {
+        self.players[player].round_time.is_none()
+    }
+
+    /// Stop the timer of a player and record their solve.
+    ///
+    /// Once both players have finished, the round is scored and a new scramble is generated.
+    pub fn finish(&mut self, player: usize) {
+        let p = &mut self.players[player];
+        p.timer.stop();
+        p.round_time = Some(p.timer.solve_time);
+        p.session.add_solve(data::Solve::new(
+            p.timer.solve_time,
+            self.scramble.to_string(),
+        ));
+
+        if let [Player {
+            round_time: Some(first),
+            ..
+        }, Player {
+            round_time: Some(second),
+            ..
+        }] = &self.players
+        {
+            let outcome = match first.cmp(second) {
+                std::cmp::Ordering::Less => RoundOutcome::Win(0),
+                std::cmp::Ordering::Greater => RoundOutcome::Win(1),
+                std::cmp::Ordering::Equal => RoundOutcome::Draw,
+            };
+            match outcome {
+                RoundOutcome::Win(winner) => self.players[winner].wins += 1,
+                RoundOutcome::Draw => self.draws += 1,
+            }
+            self.last_outcome = Some(outcome);
+
+            for p in self.players.iter_mut() {
+                p.round_time = None;
+            }
+            self.scramble = data::scramble::scramble_cube(3);
+        }
+    }
+}
+
+impl Default for Battle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KTApplication {
+    pub(super) fn battle_view(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let battle = &self.battle;
+
+        let scoreboard = {
+            let [first, second] = &battle.players;
+            let outcome = match battle.last_outcome {
+                Some(RoundOutcome::Win(winner)) => {
+                    format!("{} won the last round", battle.players[winner].name)
+                }
+                Some(RoundOutcome::Draw) => String::from("The last round was a draw"),
+                None => String::from("Fastest time wins the round"),
+            };
+            widget::column![
+                widget::text(format!(
+                    "{}  {} – {}  {}",
+                    first.name, first.wins, second.wins, second.name
+                ))
+                .size(28),
+                widget::text(format!("Draws: {}", battle.draws)).style(tangible::theme::Text::Dim),
+                widget::text(outcome).style(tangible::theme::Text::Dim),
+            ]
+            .spacing(4)
+            .align_items(Alignment::Center)
+        };
+
+        let players = widget::row(
+            battle
+                .players
+                .iter()
+                .enumerate()
+                .map(|(i, p)| self.battle_player(i, p))
+                .collect(),
+        )
+        .spacing(16)
+        .height(Length::Fill);
+
+        let mut content = widget::column![].spacing(16).align_items(Alignment::Center);
+        if !battle.is_running() {
+            content = content.push(
+                widget::text(battle.scramble.to_string())
+                    .size(24)
+                    .horizontal_alignment(alignment::Horizontal::Center),
+            );
+        }
+
+        widget::container(content.push(players).push(scoreboard))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(16)
+            .center_x()
+            .into()
+    }
+
+    fn battle_player<'a>(
+        &'a self,
+        index: usize,
+        player: &'a Player,
+    ) -> iced::Element<'a, Message, iced::Renderer<tangible::Theme>> {
+        let mut column = widget::column![widget::text(player.name).size(24)]
+            .spacing(12)
+            .align_items(Alignment::Center);
+
+        column = column.push(self.timer_text(&player.timer, 80.0));
+        if let Some(progress_bar) = self.hold_progress_bar(&player.timer) {
+            column = column.push(progress_bar);
+        }
+
+        let status = if !self.battle.can_start(index) {
+            String::from("Waiting for opponent")
+        } else if let State::Idle { .. } | State::Finished = player.timer.state {
+            format!("Hold {} to start", player.key_name)
+        } else {
+            String::new()
+        };
+        column = column.push(widget::text(status).style(tangible::theme::Text::Dim));
+
+        column = column.push(
+            widget::column![
+                stat_row(
+                    "Ao5",
+                    player
+                        .session
+                        .last_ao5()
+                        .map_or("--".to_string(), |s| s.to_string()),
+                ),
+                stat_row(
+                    "Ao12",
+                    player
+                        .session
+                        .last_ao12()
+                        .map_or("--".to_string(), |s| s.to_string()),
+                ),
+            ]
+            .spacing(8),
+        );
+
+        widget::container(column)
+            .width(Length::FillPortion(1))
+            .center_x()
+            .center_y()
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use iced::{widget, Alignment, Length};
+
+use crate::tangible::pattern::stat_row;
+use crate::{data, tangible};
+
+use super::{display_whole_seconds, KTApplication, Message};
+
+/// Cutoff of a competition round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cutoff(pub Option<Duration>);
+
+impl Cutoff {
+    pub const ALL: [Self; 6] = [
+        Self(None),
+        Self(Some(Duration::from_secs(30))),
+        Self(Some(Duration::from_secs(60))),
+        Self(Some(Duration::from_secs(120))),
+        Self(Some(Duration::from_secs(180))),
+        Self(Some(Duration::from_secs(300))),
+    ];
+}
+
+impl std::fmt::Display for Cutoff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(cutoff) => write!(f, "Cutoff {}", display_whole_seconds(&cutoff)),
+            None => write!(f, "No cutoff"),
+        }
+    }
+}
+
+/// Time limit of the attempts of a competition round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeLimit(pub Option<Duration>);
+
+impl TimeLimit {
+    pub const ALL: [Self; 6] = [
+        Self(None),
+        Self(Some(Duration::from_secs(60))),
+        Self(Some(Duration::from_secs(120))),
+        Self(Some(Duration::from_secs(300))),
+        Self(Some(Duration::from_secs(600))),
+        Self(Some(Duration::from_secs(1200))),
+    ];
+}
+
+impl Default for TimeLimit {
+    fn default() -> Self {
+        Self(Some(Duration::from_secs(600)))
+    }
+}
+
+impl std::fmt::Display for TimeLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(time_limit) => write!(f, "Time limit {}", display_whole_seconds(&time_limit)),
+            None => write!(f, "No time limit"),
+        }
+    }
+}
+
+impl KTApplication {
+    /// Start a new round with the given rules, keeping the current one if it has results.
+    pub(super) fn new_round(
+        &mut self,
+        format: data::RoundFormat,
+        cutoff: Cutoff,
+        time_limit: TimeLimit,
+    ) {
+        let round = std::mem::replace(
+            &mut self.round,
+            data::Round::new(format, cutoff.0, time_limit.0),
+        );
+        if !round.attempts().is_empty() {
+            self.round_results.push(round);
+        }
+        self.link_to_last_solve = false;
+    }
+
+    /// Return `true` if the rules of the round can be changed, that is if it was not
+    /// started or is finished.
+    pub(super) fn can_change_round(&self) -> bool {
+        self.round.attempts().is_empty() || self.round.is_finished()
+    }
+
+    pub(super) fn competition_options(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        widget::row![
+            widget::pick_list(
+                &data::RoundFormat::ALL[..],
+                Some(self.round.format),
+                Message::RoundFormatSelected,
+            )
+            .padding([4, 8]),
+            widget::pick_list(
+                &Cutoff::ALL[..],
+                Some(Cutoff(self.round.cutoff)),
+                Message::CutoffSelected,
+            )
+            .padding([4, 8]),
+            widget::pick_list(
+                &TimeLimit::ALL[..],
+                Some(TimeLimit(self.round.time_limit)),
+                Message::TimeLimitSelected,
+            )
+            .padding([4, 8]),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    /// Attempts and result of the current round.
+    pub(super) fn round_summary(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let round = &self.round;
+        let format = round.format;
+
+        let attempts = widget::row(
+            (0..format.attempts())
+                .map(|i| {
+                    let text = match round.attempts().get(i) {
+                        Some(solve) => solve.time.to_string(),
+                        None if round.is_finished() => String::from("--"),
+                        None => String::from("..."),
+                    };
+                    widget::text(text).into()
+                })
+                .collect(),
+        )
+        .spacing(16);
+
+        let status = if round.is_finished() {
+            match round.made_cutoff() {
+                Some(false) => String::from("Cutoff missed, round over"),
+                _ => String::from("Round over"),
+            }
+        } else {
+            let mut status = format!(
+                "Attempt {} of {}",
+                round.attempts().len() + 1,
+                format.attempts()
+            );
+            if let (Some(cutoff), None) = (round.cutoff, round.made_cutoff()) {
+                status += &format!(", sub-{} to continue", display_whole_seconds(&cutoff));
+            }
+            status
+        };
+
+        let mut column = widget::column![
+            attempts,
+            widget::text(status).style(tangible::theme::Text::Dim),
+            stat_row(
+                "Best",
+                round.best().map_or("--".to_string(), |s| s.to_string()),
+            ),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center);
+
+        if format.is_ranked_by_average() {
+            column = column.push(stat_row(
+                match format {
+                    data::RoundFormat::MeanOf3 => "Mean",
+                    _ => "Average",
+                },
+                round.average().map_or("--".to_string(), |s| s.to_string()),
+            ));
+        }
+
+        if let Some(best_round) = self
+            .round_results
+            .iter()
+            .filter(|r| r.format == format)
+            .filter_map(|r| match format.is_ranked_by_average() {
+                true => r.average(),
+                false => r.best(),
+            })
+            .min()
+        {
+            column = column.push(stat_row("Best round", best_round.to_string()));
+        }
+
+        widget::container(column).width(Length::Shrink).into()
+    }
+}
@@ -0,0 +1,97 @@
+use iced::{widget, window, Alignment, Command, Length};
+
+use crate::tangible;
+
+use super::{shortcuts::Shortcut, KTApplication, Message, Page, TimerMode};
+
+impl KTApplication {
+    /// Whether the current page can be shown in focus mode.
+    ///
+    /// Battle and fewest moves modes have their own timers, which are always shown
+    /// in full.
+    pub(super) fn can_focus(&self) -> bool {
+        self.page == Page::Timer && !matches!(self.mode, TimerMode::Battle | TimerMode::FewestMoves)
+    }
+
+    /// Whether the timer page is shown in focus mode.
+    pub(super) fn in_focus(&self) -> bool {
+        self.preferences.focus.enabled && self.can_focus()
+    }
+
+    /// Mode of the window, fullscreen while the timer page is shown in focus mode if
+    /// the preferences say so.
+    pub(super) fn window_mode(&self) -> window::Mode {
+        if self.in_focus() && self.preferences.focus.fullscreen {
+            window::Mode::Fullscreen
+        } else {
+            window::Mode::Windowed
+        }
+    }
+
+    /// Change the mode of the window if focus mode was entered or left by changing
+    /// the page or the timer mode.
+    pub(super) fn update_window_mode(&self, was_in_focus: bool) -> Command<Message> {
+        if self.in_focus() == was_in_focus {
+            Command::none()
+        } else {
+            window::change_mode(self.window_mode())
+        }
+    }
+
+    /// Enter or leave focus mode, unless a solve is being timed.
+    ///
+    /// The solve details and the dialog are closed, as focus mode does not show them.
+    pub(super) fn toggle_focus(&mut self) -> Command<Message> {
+        if self.timer.state.is_running() || !self.can_focus() {
+            return Command::none();
+        }
+        self.preferences.focus.enabled = !self.preferences.focus.enabled;
+        self.selected_solve = None;
+        self.dialog = None;
+        self.save_preferences();
+        window::change_mode(self.window_mode())
+    }
+
+    /// The scramble and the timer, without anything else to look at.
+    pub(super) fn focus_view(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let running = self.timer.state.is_running();
+        let mut column = widget::column![].spacing(16).align_items(Alignment::Center);
+
+        if !running && self.mode != TimerMode::MultiBlind {
+            column = column.push(self.scramble_text());
+        }
+        column = column.push(self.timer_text(&self.timer, 120.0));
+        if let Some(progress_bar) = self.hold_progress_bar(&self.timer) {
+            column = column.push(progress_bar);
+        }
+        if let Some(ready_label) = self.ready_label() {
+            column = column.push(ready_label);
+        }
+        let has_averages = !matches!(self.mode, TimerMode::Competition | TimerMode::Trainer);
+        if !running && has_averages && !self.preferences.focus.hide_averages {
+            column = column.push(self.session_stats());
+        }
+        if !running {
+            column = column.push(
+                widget::text(format!(
+                    "Press {} or Escape to leave focus mode",
+                    self.preferences.shortcuts.key(Shortcut::Focus)
+                ))
+                .style(tangible::theme::Text::Dim),
+            );
+            column = column.push(
+                widget::button("Leave focus")
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Flat)
+                    .on_press(Message::FocusToggled),
+            );
+        }
+
+        widget::container(column)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
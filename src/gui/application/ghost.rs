@@ -0,0 +1,147 @@
+use iced::{widget, Alignment, Application, Length};
+
+use crate::tangible::pattern::stat_row;
+use crate::{data, tangible};
+
+use super::{KTApplication, Message, State, TimerDisplay, TimerMode};
+
+/// A previous session that can be raced as a ghost.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GhostChoice {
+    #[default]
+    None,
+    /// The previous session at `index`, with its number of solves.
+    Session { index: usize, n_solves: usize },
+}
+
+impl std::fmt::Display for GhostChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "No ghost"),
+            Self::Session { index, n_solves } => {
+                write!(f, "Ghost: session {} ({} solves)", index + 1, n_solves)
+            }
+        }
+    }
+}
+
+impl KTApplication {
+    /// Get the choices of ghost among the previous sessions.
+    pub(super) fn ghost_choices(&self) -> Vec<GhostChoice> {
+        std::iter::once(GhostChoice::None)
+            .chain(
+                self.previous_sessions
+                    .iter()
+                    .enumerate()
+                    .map(|(index, session)| GhostChoice::Session {
+                        index,
+                        n_solves: session.get_n_solves(),
+                    }),
+            )
+            .collect()
+    }
+
+    /// Buttons to start a new session and to choose the scramble type and a ghost.
+    pub(super) fn session_options(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let mut row = widget::row![
+            widget::button("New session")
+                .padding([4, 8])
+                .on_press(Message::NewSession),
+            widget::pick_list(
+                &data::scramble::ScrambleType::ALL[..],
+                Some(self.session.scramble_type),
+                Message::ScrambleTypeSelected,
+            )
+            .padding([4, 8])
+            .width(Length::Shrink),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center);
+
+        if !self.previous_sessions.is_empty() {
+            row = row.push(
+                widget::pick_list(
+                    self.ghost_choices(),
+                    Some(self.ghost_choice),
+                    Message::GhostSelected,
+                )
+                .padding([4, 8])
+                .width(Length::Shrink),
+            );
+        }
+
+        row.into()
+    }
+
+    /// Time of the ghost for the current solve and the delta with the running time.
+    ///
+    /// After a solve, the delta of the last solve is shown instead. While running, nothing is
+    /// shown unless the timer display shows the running time, as the ghost time and the delta
+    /// would reveal it.
+    pub(super) fn ghost_delta(
+        &self,
+    ) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        // Competition attempts and trained cases are not recorded in the session.
+        if matches!(self.mode, TimerMode::Competition | TimerMode::Trainer) {
+            return None;
+        }
+
+        let ghost = self.ghost.as_ref()?;
+        let running = matches!(self.timer.state, State::Memo { .. } | State::Timing { .. });
+        if running
+            && matches!(
+                self.preferences.timer_display,
+                TimerDisplay::Label | TimerDisplay::Hidden
+            )
+        {
+            return None;
+        }
+        let n_solves = self.session.get_n_solves();
+        let index = if !running && self.link_to_last_solve {
+            n_solves.checked_sub(1)?
+        } else {
+            n_solves
+        };
+        let ghost_time = ghost.time(index)?;
+
+        let mut row = widget::row![
+            widget::text(format!("Ghost {}", ghost_time)).style(tangible::theme::Text::Dim)
+        ]
+        .spacing(8);
+
+        let time = match running || self.link_to_last_solve {
+            true => self.timer.solve_time.recorded_time(),
+            false => None,
+        };
+        if let Some(delta) = time.and_then(|time| ghost.delta(index, time)) {
+            let theme = self.theme();
+            let palette = theme.palette();
+            let color = match delta {
+                data::GhostDelta::Ahead(_) => palette.success.base,
+                data::GhostDelta::Behind(_) => palette.destructive.base,
+            };
+            row = row.push(widget::text(delta).style(color));
+        }
+
+        Some(row.into())
+    }
+
+    /// How many solves and averages of the session beat the ghost.
+    pub(super) fn ghost_summary(
+        &self,
+    ) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        let summary = self.ghost.as_ref()?.summary(&self.session);
+
+        Some(
+            widget::column![
+                stat_row("Solves beaten", summary.solves.to_string()),
+                stat_row("Ao5 beaten", summary.ao5.to_string()),
+                stat_row("Ao12 beaten", summary.ao12.to_string()),
+            ]
+            .spacing(8)
+            .into(),
+        )
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local, NaiveDate};
+use iced::{widget, Alignment, Application, Length};
+
+use crate::{data, tangible};
+
+use super::{KTApplication, Message};
+
+/// Statistics a goal can be set for.
+pub const GOAL_STATISTICS: [data::GoalStatistic; 7] = [
+    data::GoalStatistic::Single,
+    data::GoalStatistic::Average(5),
+    data::GoalStatistic::Average(12),
+    data::GoalStatistic::Average(50),
+    data::GoalStatistic::Average(100),
+    data::GoalStatistic::Average(1000),
+    data::GoalStatistic::Mean,
+];
+
+/// Input of a new goal.
+pub struct GoalForm {
+    pub statistic: data::GoalStatistic,
+    pub target: String,
+    /// Deadline as `YYYY-MM-DD`, or empty for no deadline.
+    pub deadline: String,
+    pub error: Option<String>,
+}
+
+impl GoalForm {
+    pub fn new() -> Self {
+        Self {
+            statistic: data::GoalStatistic::Average(5),
+            target: String::new(),
+            deadline: String::new(),
+            error: None,
+        }
+    }
+
+    /// Create a goal from the input.
+    pub fn parse(&self) -> Result<data::Goal, String> {
+        let target: data::SolveTime = self.target.parse().map_err(|e| format!("{}", e))?;
+        if target.is_dnf() {
+            return Err(String::from("the target cannot be DNF"));
+        }
+
+        let deadline = match self.deadline.trim() {
+            "" => None,
+            deadline => Some(
+                NaiveDate::parse_from_str(deadline, "%Y-%m-%d")
+                    .map_err(|_| format!("invalid date `{}`, use YYYY-MM-DD", deadline))?,
+            ),
+        };
+
+        Ok(data::Goal::new(self.statistic, target, deadline))
+    }
+}
+
+impl Default for GoalForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KTApplication {
+    /// Evaluate the goals against the session, notifying of the goals just reached.
+    pub(super) fn evaluate_goals(&mut self) {
+        let now = SystemTime::now();
+        for goal in self.goals.iter_mut() {
+            if goal.evaluate(&self.session, now) {
+                self.goal_notification = Some(format!("Goal reached: {}", goal));
+            }
+        }
+    }
+
+    pub(super) fn goals_view(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let form = &self.goal_form;
+        let mut form_column = widget::column![widget::row![
+            widget::pick_list(
+                &GOAL_STATISTICS[..],
+                Some(form.statistic),
+                Message::GoalStatisticSelected,
+            )
+            .padding([4, 8]),
+            widget::text_input("Target, e.g. 15.00", &form.target)
+                .on_input(Message::GoalTargetChanged)
+                .on_submit(Message::GoalAdd)
+                .padding([4, 8])
+                .width(Length::Fixed(160.0)),
+            widget::text_input("Deadline (YYYY-MM-DD)", &form.deadline)
+                .on_input(Message::GoalDeadlineChanged)
+                .on_submit(Message::GoalAdd)
+                .padding([4, 8])
+                .width(Length::Fixed(200.0)),
+            widget::button("Add goal")
+                .padding([4, 8])
+                .style(tangible::theme::Button::Suggested)
+                .on_press(Message::GoalAdd),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center)]
+        .spacing(8);
+        if let Some(error) = &form.error {
+            let theme = self.theme();
+            let palette = theme.palette();
+            form_column = form_column.push(widget::text(error).style(palette.error.base));
+        }
+
+        let goals: Vec<_> = self
+            .goals
+            .iter()
+            .enumerate()
+            .map(|(i, goal)| self.goal_card(i, goal))
+            .collect();
+        let goals = if goals.is_empty() {
+            iced::Element::from(
+                widget::text("No goals yet. Set a target above.").style(tangible::theme::Text::Dim),
+            )
+        } else {
+            widget::scrollable(widget::column(goals).spacing(8).width(Length::Fill)).into()
+        };
+
+        widget::container(
+            widget::column![widget::text("Goals").size(32), form_column, goals]
+                .spacing(16)
+                .max_width(700),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(16)
+        .center_x()
+        .into()
+    }
+
+    fn goal_card<'a>(
+        &'a self,
+        index: usize,
+        goal: &'a data::Goal,
+    ) -> iced::Element<'a, Message, iced::Renderer<tangible::Theme>> {
+        let best = goal
+            .statistic
+            .best(&self.session)
+            .map_or("--".to_string(), |b| b.to_string());
+        let status = match goal.status {
+            data::GoalStatus::InProgress => match goal.deadline {
+                Some(deadline) => {
+                    let today = Local::now().date_naive();
+                    format!(
+                        "Best {}, {} days left",
+                        best,
+                        (deadline - today).num_days() + 1
+                    )
+                }
+                None => format!("Best {}", best),
+            },
+            data::GoalStatus::Reached(time) => format!(
+                "Reached on {}",
+                DateTime::<Local>::from(time).format("%Y-%m-%d")
+            ),
+            data::GoalStatus::Missed => format!("Missed, best {}", best),
+        };
+        let progress_style = match goal.status {
+            data::GoalStatus::InProgress => tangible::theme::ProgressBar::Accent,
+            data::GoalStatus::Reached(_) => tangible::theme::ProgressBar::Success,
+            data::GoalStatus::Missed => tangible::theme::ProgressBar::Error,
+        };
+
+        let content = widget::column![
+            widget::row![
+                widget::text(goal).size(20).width(Length::Fill),
+                widget::button("Remove")
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Flat)
+                    .on_press(Message::GoalRemove(index)),
+            ]
+            .align_items(Alignment::Center),
+            widget::text(status).style(tangible::theme::Text::Dim),
+            widget::progress_bar(0.0..=1.0, goal.progress(&self.session))
+                .style(progress_style)
+                .height(Length::Fixed(6.0)),
+        ]
+        .spacing(8);
+
+        widget::container(content)
+            .style(self.theme().palette().card)
+            .padding(12)
+            .width(Length::Fill)
+            .into()
+    }
+}
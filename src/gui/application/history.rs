@@ -0,0 +1,161 @@
+use chrono::{Datelike, Days, Local, NaiveDate};
+use iced::{alignment, widget, Alignment, Application, Length};
+
+use crate::tangible::pattern::stat_row;
+use crate::{data, tangible};
+
+use super::{KTApplication, Message};
+
+/// Number of weeks shown in the heatmap.
+const HEATMAP_WEEKS: u64 = 26;
+
+/// Number of recent practice days listed under the heatmap.
+const RECENT_DAYS: usize = 14;
+
+impl KTApplication {
+    /// Get the practice history of every session.
+    fn history(&self) -> data::History {
+        data::History::new(self.previous_sessions.iter().chain([&self.session]))
+    }
+
+    pub(super) fn history_view(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let history = self.history();
+        let today = Local::now().date_naive();
+
+        let streaks = widget::column![
+            stat_row(
+                "Current streak",
+                format!("{} days", history.current_streak(today))
+            ),
+            stat_row(
+                "Longest streak",
+                format!("{} days", history.longest_streak())
+            ),
+            stat_row("Practice days", history.days().count().to_string()),
+        ]
+        .spacing(8);
+
+        let recent = widget::column(
+            history
+                .days()
+                .rev()
+                .take(RECENT_DAYS)
+                .map(|day| {
+                    widget::row![
+                        widget::text(day.date.format("%a %Y-%m-%d")).width(Length::FillPortion(2)),
+                        widget::text(format!("{} solves", day.n_solves))
+                            .width(Length::FillPortion(1))
+                            .horizontal_alignment(alignment::Horizontal::Right),
+                        widget::text(day.mean().map_or("--".to_string(), |m| m.to_string()))
+                            .width(Length::FillPortion(1))
+                            .horizontal_alignment(alignment::Horizontal::Right),
+                    ]
+                    .spacing(8)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(4);
+
+        widget::container(widget::scrollable(
+            widget::column![
+                widget::text("Practice history").size(32),
+                streaks,
+                self.heatmap(&history, today),
+                widget::text("Recent days").size(20),
+                recent,
+            ]
+            .spacing(16)
+            .max_width(700),
+        ))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(16)
+        .center_x()
+        .into()
+    }
+
+    /// Calendar heatmap of the solve counts of the last weeks, one column per week.
+    ///
+    /// Hovering a day shows its solve count and mean.
+    fn heatmap(
+        &self,
+        history: &data::History,
+        today: NaiveDate,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let theme = self.theme();
+        let palette = theme.palette();
+        let start = today
+            .checked_sub_days(Days::new(
+                (HEATMAP_WEEKS - 1) * 7 + today.weekday().num_days_from_monday() as u64,
+            ))
+            .unwrap_or(today);
+
+        let weeks = (0..HEATMAP_WEEKS)
+            .map(|week| {
+                let days = (0..7)
+                    .map(|weekday| {
+                        let date = start.checked_add_days(Days::new(week * 7 + weekday));
+                        let cell = widget::container(widget::Space::new(
+                            Length::Fixed(14.0),
+                            Length::Fixed(14.0),
+                        ));
+                        match date.filter(|&date| date <= today) {
+                            Some(date) => {
+                                let day = history.day(date);
+                                let n_solves = day.map_or(0, |d| d.n_solves);
+                                let mut bg = palette.accent.base;
+                                bg.a = match n_solves {
+                                    0 => 0.0,
+                                    1..=4 => 0.25,
+                                    5..=19 => 0.5,
+                                    20..=49 => 0.75,
+                                    _ => 1.0,
+                                };
+                                if n_solves == 0 {
+                                    bg = palette.neutral.bg.base;
+                                }
+                                let summary = match day {
+                                    Some(day) => format!(
+                                        "{}: {} solves, mean {}",
+                                        date.format("%a %Y-%m-%d"),
+                                        day.n_solves,
+                                        day.mean().map_or("--".to_string(), |m| m.to_string())
+                                    ),
+                                    None => format!("{}: no solves", date.format("%a %Y-%m-%d")),
+                                };
+                                widget::tooltip(
+                                    cell.style(tangible::theme::ColorGroup {
+                                        bg: tangible::theme::BackgroundColor::new(bg),
+                                        ..palette.card
+                                    }),
+                                    summary,
+                                    widget::tooltip::Position::Top,
+                                )
+                                .padding(4)
+                                .style(palette.card)
+                                .into()
+                            }
+                            None => cell.into(),
+                        }
+                    })
+                    .collect();
+                widget::column(days).spacing(3).into()
+            })
+            .collect();
+
+        widget::column![
+            widget::row(weeks).spacing(3),
+            widget::text(
+                "Each square is a day, in a stronger color with more solves. \
+                Hover a day to see its mean."
+            )
+            .style(tangible::theme::Text::Dim),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Start)
+        .into()
+    }
+}
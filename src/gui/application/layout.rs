@@ -0,0 +1,497 @@
+use std::time::Duration;
+
+use iced::{
+    widget::{self, pane_grid},
+    Alignment, Command, Length,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::tangible;
+
+use super::{KTApplication, Message, TimerMode};
+
+/// A panel of the timer page, shown in a pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Panel {
+    Timer,
+    Solves,
+    Statistics,
+    Graph,
+    Scramble,
+}
+
+impl Panel {
+    pub const ALL: [Self; 5] = [
+        Self::Timer,
+        Self::Solves,
+        Self::Statistics,
+        Self::Graph,
+        Self::Scramble,
+    ];
+}
+
+impl std::fmt::Display for Panel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Timer => "Timer",
+                Self::Solves => "Solves",
+                Self::Statistics => "Statistics",
+                Self::Graph => "Graph",
+                Self::Scramble => "Scramble",
+            }
+        )
+    }
+}
+
+/// Direction of the line splitting two panes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitAxis {
+    /// Panes above each other.
+    Horizontal,
+    /// Panes side by side.
+    Vertical,
+}
+
+impl From<SplitAxis> for pane_grid::Axis {
+    fn from(axis: SplitAxis) -> Self {
+        match axis {
+            SplitAxis::Horizontal => Self::Horizontal,
+            SplitAxis::Vertical => Self::Vertical,
+        }
+    }
+}
+
+impl From<pane_grid::Axis> for SplitAxis {
+    fn from(axis: pane_grid::Axis) -> Self {
+        match axis {
+            pane_grid::Axis::Horizontal => Self::Horizontal,
+            pane_grid::Axis::Vertical => Self::Vertical,
+        }
+    }
+}
+
+/// A pane showing a panel, or a split between two nodes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LayoutNode {
+    Panel(Panel),
+    Split {
+        axis: SplitAxis,
+        /// Share of the first node, between 0 and 1.
+        ratio: f32,
+        a: Box<LayoutNode>,
+        b: Box<LayoutNode>,
+    },
+}
+
+impl LayoutNode {
+    fn split(axis: SplitAxis, ratio: f32, a: impl Into<Self>, b: impl Into<Self>) -> Self {
+        Self::Split {
+            axis,
+            ratio,
+            a: Box::new(a.into()),
+            b: Box::new(b.into()),
+        }
+    }
+
+    fn push_panels(&self, panels: &mut Vec<Panel>) {
+        match self {
+            Self::Panel(panel) => panels.push(*panel),
+            Self::Split { a, b, .. } => {
+                a.push_panels(panels);
+                b.push_panels(panels);
+            }
+        }
+    }
+
+    fn ratios_are_valid(&self) -> bool {
+        match self {
+            Self::Panel(_) => true,
+            Self::Split { ratio, a, b, .. } => {
+                (0.0..=1.0).contains(ratio) && a.ratios_are_valid() && b.ratios_are_valid()
+            }
+        }
+    }
+
+    fn configuration(&self) -> pane_grid::Configuration<Panel> {
+        match self {
+            Self::Panel(panel) => pane_grid::Configuration::Pane(*panel),
+            Self::Split { axis, ratio, a, b } => pane_grid::Configuration::Split {
+                axis: (*axis).into(),
+                ratio: *ratio,
+                a: Box::new(a.configuration()),
+                b: Box::new(b.configuration()),
+            },
+        }
+    }
+
+    fn from_node(node: &pane_grid::Node, panes: &pane_grid::State<Panel>) -> Self {
+        match node {
+            pane_grid::Node::Pane(pane) => {
+                Self::Panel(*panes.get(pane).expect("panes of the layout exist"))
+            }
+            pane_grid::Node::Split {
+                axis, ratio, a, b, ..
+            } => Self::Split {
+                axis: (*axis).into(),
+                ratio: *ratio,
+                a: Box::new(Self::from_node(a, panes)),
+                b: Box::new(Self::from_node(b, panes)),
+            },
+        }
+    }
+}
+
+impl From<Panel> for LayoutNode {
+    fn from(panel: Panel) -> Self {
+        Self::Panel(panel)
+    }
+}
+
+/// Arrangement of the panels of the timer page, where each panel is shown at most
+/// once and the timer is always shown.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct PaneLayout(LayoutNode);
+
+impl PaneLayout {
+    /// Create a layout, or `None` if a panel is shown twice, the timer is not
+    /// shown, or a split ratio is not between 0 and 1.
+    pub fn new(root: LayoutNode) -> Option<Self> {
+        let mut panels = Vec::new();
+        root.push_panels(&mut panels);
+        let unique = Panel::ALL
+            .iter()
+            .all(|panel| panels.iter().filter(|&p| p == panel).count() <= 1);
+        let valid = unique && panels.contains(&Panel::Timer) && root.ratios_are_valid();
+        valid.then_some(Self(root))
+    }
+
+    /// Read the layout of a pane grid.
+    pub fn from_panes(panes: &pane_grid::State<Panel>) -> Self {
+        Self(LayoutNode::from_node(panes.layout(), panes))
+    }
+
+    /// Create a pane grid with this layout.
+    pub fn panes(&self) -> pane_grid::State<Panel> {
+        pane_grid::State::with_configuration(self.0.configuration())
+    }
+
+    /// Panels shown, from left to right and top to bottom.
+    pub fn panels(&self) -> Vec<Panel> {
+        let mut panels = Vec::new();
+        self.0.push_panels(&mut panels);
+        panels
+    }
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        LayoutPreset::Classic.layout()
+    }
+}
+
+impl<'de> Deserialize<'de> for PaneLayout {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(LayoutNode::deserialize(deserializer)?).ok_or_else(|| {
+            serde::de::Error::custom(
+                "expected each panel at most once, with the timer, and ratios from 0 to 1",
+            )
+        })
+    }
+}
+
+/// A predefined arrangement of the panels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutPreset {
+    /// The solves on the left of the timer.
+    Classic,
+    /// The solves on the left of the timer, with statistics, a graph and the scramble
+    /// below the timer.
+    Dashboard,
+    /// The timer above the solves, for small windows.
+    Compact,
+}
+
+impl LayoutPreset {
+    pub const ALL: [Self; 3] = [Self::Classic, Self::Dashboard, Self::Compact];
+
+    pub fn layout(&self) -> PaneLayout {
+        use {Panel::*, SplitAxis::*};
+
+        let root = match self {
+            Self::Classic => LayoutNode::split(Vertical, 0.3, Solves, Timer),
+            Self::Dashboard => LayoutNode::split(
+                Vertical,
+                0.25,
+                Solves,
+                LayoutNode::split(
+                    Horizontal,
+                    0.6,
+                    Timer,
+                    LayoutNode::split(
+                        Vertical,
+                        0.34,
+                        Statistics,
+                        LayoutNode::split(Vertical, 0.5, Graph, Scramble),
+                    ),
+                ),
+            ),
+            Self::Compact => LayoutNode::split(Horizontal, 0.7, Timer, Solves),
+        };
+        PaneLayout(root)
+    }
+}
+
+impl std::fmt::Display for LayoutPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Classic => "Classic",
+                Self::Dashboard => "Dashboard",
+                Self::Compact => "Compact",
+            }
+        )
+    }
+}
+
+/// Which of the two saved layouts is shown: the compact one is for small windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutSize {
+    Regular,
+    Compact,
+}
+
+/// A change to the panes of the timer page.
+#[derive(Debug, Clone, Copy)]
+pub enum PaneAction {
+    Resized(pane_grid::ResizeEvent),
+    Dragged(pane_grid::DragEvent),
+    /// Split a pane to show a hidden panel next to it.
+    Split(pane_grid::Pane, SplitAxis),
+    Closed(pane_grid::Pane),
+    /// Show a panel in a pane, swapping it with the pane showing it if any.
+    PanelSelected(pane_grid::Pane, Panel),
+}
+
+/// Time without resizing after which the layout is saved, as a divider being dragged
+/// is resized on every move of the mouse.
+const RESIZE_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+impl KTApplication {
+    /// Apply a change to the panes, and save the new layout.
+    ///
+    /// After a resize, the layout is saved once the panes stop being resized.
+    pub(super) fn update_panes(
+        &mut self,
+        size: LayoutSize,
+        action: PaneAction,
+    ) -> Command<Message> {
+        let panes = match size {
+            LayoutSize::Regular => &mut self.panes,
+            LayoutSize::Compact => &mut self.compact_panes,
+        };
+        let resized = matches!(action, PaneAction::Resized(_));
+        match action {
+            PaneAction::Resized(pane_grid::ResizeEvent { split, ratio }) => {
+                panes.resize(&split, ratio)
+            }
+            PaneAction::Dragged(pane_grid::DragEvent::Dropped { pane, target }) => {
+                panes.swap(&pane, &target)
+            }
+            PaneAction::Dragged(_) => return Command::none(),
+            PaneAction::Split(pane, axis) => {
+                let shown = PaneLayout::from_panes(panes).panels();
+                if let Some(&panel) = Panel::ALL.iter().find(|panel| !shown.contains(panel)) {
+                    panes.split(axis.into(), &pane, panel);
+                }
+            }
+            PaneAction::Closed(pane) => {
+                // The timer is always shown.
+                if panes.get(&pane) != Some(&Panel::Timer) {
+                    panes.close(&pane);
+                }
+            }
+            PaneAction::PanelSelected(pane, panel) => {
+                let other = panes.iter().find(|(_, &p)| p == panel).map(|(&p, _)| p);
+                match other {
+                    Some(other) => panes.swap(&pane, &other),
+                    None => {
+                        if let Some(shown) = panes.get_mut(&pane) {
+                            if *shown != Panel::Timer {
+                                *shown = panel;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let layout = PaneLayout::from_panes(panes);
+        match size {
+            LayoutSize::Regular => self.preferences.layout = layout,
+            LayoutSize::Compact => self.preferences.compact_layout = layout,
+        }
+
+        self.layout_changes += 1;
+        if resized {
+            let changes = self.layout_changes;
+            Command::perform(async_std::task::sleep(RESIZE_SAVE_DELAY), move |()| {
+                Message::LayoutResizeEnded(changes)
+            })
+        } else {
+            self.save_preferences();
+            Command::none()
+        }
+    }
+
+    /// Save the layout if it did not change since the resize that scheduled the save.
+    pub(super) fn end_layout_resize(&mut self, changes: u64) {
+        if changes == self.layout_changes {
+            self.save_preferences();
+        }
+    }
+
+    /// Replace a layout with a preset.
+    pub(super) fn apply_layout_preset(&mut self, size: LayoutSize, preset: LayoutPreset) {
+        let layout = preset.layout();
+        match size {
+            LayoutSize::Regular => {
+                self.panes = layout.panes();
+                self.preferences.layout = layout;
+            }
+            LayoutSize::Compact => {
+                self.compact_panes = layout.panes();
+                self.preferences.compact_layout = layout;
+            }
+        }
+        self.save_preferences();
+    }
+
+    /// Panels of the timer page, in their saved layout.
+    pub(super) fn panes_view(
+        &self,
+        size: LayoutSize,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let panes = match size {
+            LayoutSize::Regular => &self.panes,
+            LayoutSize::Compact => &self.compact_panes,
+        };
+        let can_split = panes.len() < Panel::ALL.len();
+
+        widget::PaneGrid::new(panes, move |pane, &panel, _maximized| {
+            let mut controls = widget::row![].spacing(4).align_items(Alignment::Center);
+            if panel != Panel::Timer {
+                controls = controls.push(
+                    widget::pick_list(&Panel::ALL[1..], Some(panel), move |panel| {
+                        Message::PanesChanged(size, PaneAction::PanelSelected(pane, panel))
+                    })
+                    .padding([2, 8]),
+                );
+            }
+            if can_split {
+                for (label, axis) in [
+                    ("Split right", SplitAxis::Vertical),
+                    ("Split down", SplitAxis::Horizontal),
+                ] {
+                    controls = controls.push(
+                        widget::button(label)
+                            .padding([2, 8])
+                            .style(tangible::theme::Button::Flat)
+                            .on_press(Message::PanesChanged(size, PaneAction::Split(pane, axis))),
+                    );
+                }
+            }
+            if panel != Panel::Timer {
+                controls = controls.push(
+                    widget::button("Close")
+                        .padding([2, 8])
+                        .style(tangible::theme::Button::Flat)
+                        .on_press(Message::PanesChanged(size, PaneAction::Closed(pane))),
+                );
+            }
+
+            let title_bar =
+                pane_grid::TitleBar::new(widget::text(panel).style(tangible::theme::Text::Dim))
+                    .controls(controls)
+                    .padding([2, 8])
+                    .style(tangible::theme::Container::Solid(
+                        tangible::theme::NamedColor::Header,
+                    ));
+            pane_grid::Content::new(self.panel_view(panel)).title_bar(title_bar)
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .spacing(1)
+        .on_drag(move |event| Message::PanesChanged(size, PaneAction::Dragged(event)))
+        .on_resize(8, move |event| {
+            Message::PanesChanged(size, PaneAction::Resized(event))
+        })
+        .into()
+    }
+
+    fn panel_view(
+        &self,
+        panel: Panel,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        match panel {
+            Panel::Timer => self.center_panel(),
+            Panel::Solves => match self.mode {
+                TimerMode::Trainer => self.trainer_sidebar(),
+                _ => self.sidebar(),
+            },
+            Panel::Statistics => self.statistics_panel(),
+            Panel::Graph => self.graph_panel(),
+            Panel::Scramble => self.scramble_panel(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_and_write_layouts() {
+        for preset in LayoutPreset::ALL {
+            let layout = preset.layout();
+            assert!(PaneLayout::new(layout.0.clone()).is_some(), "{}", preset);
+            assert_eq!(PaneLayout::from_panes(&layout.panes()), layout);
+
+            let text = toml::to_string(&toml::Table::from_iter([(
+                "layout".to_string(),
+                toml::Value::try_from(&layout).unwrap(),
+            )]))
+            .unwrap();
+            let table: toml::Table = text.parse().unwrap();
+            let read: PaneLayout = table["layout"].clone().try_into().unwrap();
+            assert_eq!(read, layout, "{}", text);
+        }
+        assert_eq!(
+            LayoutPreset::Dashboard.layout().panels(),
+            [
+                Panel::Solves,
+                Panel::Timer,
+                Panel::Statistics,
+                Panel::Graph,
+                Panel::Scramble
+            ]
+        );
+
+        let invalid = [
+            LayoutNode::split(SplitAxis::Vertical, 0.5, Panel::Solves, Panel::Graph),
+            LayoutNode::split(SplitAxis::Vertical, 0.5, Panel::Timer, Panel::Timer),
+            LayoutNode::split(SplitAxis::Vertical, 1.5, Panel::Timer, Panel::Graph),
+        ];
+        for root in invalid {
+            assert_eq!(PaneLayout::new(root), None);
+        }
+    }
+}
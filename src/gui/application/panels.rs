@@ -0,0 +1,302 @@
+use iced::{
+    alignment,
+    widget::{
+        self,
+        canvas::{self, Cursor, Frame, Geometry, Path, Stroke},
+    },
+    Alignment, Application, Color, Length, Point, Rectangle, Size,
+};
+
+use crate::data::{self, Face};
+use crate::tangible::{self, pattern::stat_row};
+
+use super::{KTApplication, Message, TimerMode};
+
+/// Number of last solves drawn in the graph.
+const GRAPH_SOLVES: usize = 100;
+
+impl KTApplication {
+    /// Statistics of the whole session, with the best of each configured average.
+    pub(super) fn statistics_panel(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let (session, _) = self.shown_solves();
+        let format =
+            |time: Option<data::SolveTime>| time.map_or("--".to_string(), |t| t.to_string());
+        let best = session
+            .iter()
+            .filter(|se| se.solve.is_timed())
+            .map(|se| se.solve.time)
+            .min();
+
+        let mut column = widget::column![
+            stat_row("Solves", session.get_n_solves().to_string()),
+            stat_row("Mean", format(session.mean())),
+            stat_row("Best", format(best)),
+        ]
+        .spacing(8)
+        .padding(8)
+        .align_items(Alignment::Center);
+        for &n in self.preferences.averages.sizes() {
+            column = column
+                .push(stat_row(
+                    &format!("Ao{}", n),
+                    format(session.last_average_of(n)),
+                ))
+                .push(stat_row(
+                    &format!("Best ao{}", n),
+                    format(session.best_average_of(n)),
+                ));
+        }
+
+        widget::scrollable(widget::container(column).width(Length::Fill).center_x()).into()
+    }
+
+    /// Graph of the times of the last solves, with their averages of 5 and 12.
+    pub(super) fn graph_panel(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let (session, _) = self.shown_solves();
+        let skip = session.get_n_solves().saturating_sub(GRAPH_SOLVES);
+        let seconds = |time: Option<data::SolveTime>| {
+            time.and_then(|t| t.recorded_time())
+                .map(|d| d.as_secs_f32())
+        };
+        let entries: Vec<_> = session.iter().skip(skip).collect();
+        let graph = TimeGraph {
+            singles: entries
+                .iter()
+                .map(|se| seconds(Some(se.solve.time)))
+                .collect(),
+            ao5: entries.iter().map(|se| seconds(se.ao5)).collect(),
+            ao12: entries.iter().map(|se| seconds(se.ao12)).collect(),
+        };
+
+        let theme = self.theme();
+        let palette = theme.palette();
+        let legend = widget::row![
+            widget::text("Single").style(tangible::theme::Text::Dim),
+            widget::text("Ao5").style(palette.accent.base),
+            widget::text("Ao12").style(palette.warning.base),
+        ]
+        .spacing(16);
+
+        widget::column![
+            widget::canvas(graph)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            legend,
+        ]
+        .spacing(8)
+        .padding(8)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    /// Net of the cube scrambled with the current scramble.
+    pub(super) fn scramble_panel(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let scramble = match self.mode {
+            TimerMode::Relay => None,
+            TimerMode::Trainer => Some(&self.trainer.scramble),
+            _ => Some(&self.scramble),
+        };
+
+        let content: iced::Element<'_, Message, iced::Renderer<tangible::Theme>> = match scramble {
+            Some(scramble) => {
+                let mut cube = data::Cube::new(3);
+                cube.apply(scramble);
+                widget::canvas(ScrambleNet { cube })
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .into()
+            }
+            None => widget::text("No scramble image for relays")
+                .style(tangible::theme::Text::Dim)
+                .into(),
+        };
+
+        widget::container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(8)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
+
+/// Times in seconds of a series of solves, `None` for DNFs and missing averages.
+struct TimeGraph {
+    singles: Vec<Option<f32>>,
+    ao5: Vec<Option<f32>>,
+    ao12: Vec<Option<f32>>,
+}
+
+impl canvas::Program<Message, tangible::Theme> for TimeGraph {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        theme: &tangible::Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        const LABELS_WIDTH: f32 = 48.0;
+        const MARGIN: f32 = 8.0;
+
+        let palette = theme.palette();
+        let mut frame = Frame::new(bounds.size());
+        let times = || {
+            self.singles
+                .iter()
+                .chain(&self.ao5)
+                .chain(&self.ao12)
+                .flatten()
+        };
+        let (Some(min), Some(max)) = (
+            times().copied().reduce(f32::min),
+            times().copied().reduce(f32::max),
+        ) else {
+            return vec![frame.into_geometry()];
+        };
+
+        let left = LABELS_WIDTH;
+        let (top, bottom) = (MARGIN, bounds.height - MARGIN);
+        let width = bounds.width - left - MARGIN;
+        let x = |i: usize| left + i as f32 * width / (self.singles.len() - 1).max(1) as f32;
+        let y = |t: f32| top + (max - t) / (max - min).max(0.01) * (bottom - top);
+
+        let mut dim = palette.view.fg;
+        dim.a *= 0.5;
+        for (time, position) in [(max, top), (min, bottom)] {
+            frame.stroke(
+                &Path::line(
+                    Point::new(left, position),
+                    Point::new(left + width, position),
+                ),
+                Stroke::default()
+                    .with_color(palette.view.border)
+                    .with_width(1.0),
+            );
+            frame.fill_text(canvas::Text {
+                content: format!("{:.2}", time),
+                position: Point::new(left - MARGIN, position),
+                color: dim,
+                size: 14.0,
+                horizontal_alignment: alignment::Horizontal::Right,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Default::default()
+            });
+        }
+
+        let series = [
+            (&self.singles, dim, 1.0),
+            (&self.ao5, palette.accent.base, 2.0),
+            (&self.ao12, palette.warning.base, 2.0),
+        ];
+        for (times, color, width) in series {
+            // Lines are broken by DNFs and missing averages.
+            let path = Path::new(|builder| {
+                let mut previous = None;
+                for (i, time) in times.iter().enumerate() {
+                    if let Some(time) = time {
+                        let point = Point::new(x(i), y(*time));
+                        match previous {
+                            Some(_) => builder.line_to(point),
+                            None => builder.move_to(point),
+                        }
+                    }
+                    previous = *time;
+                }
+            });
+            frame.stroke(&path, Stroke::default().with_color(color).with_width(width));
+        }
+
+        for (i, time) in self.singles.iter().enumerate() {
+            if time.is_none() {
+                frame.fill(
+                    &Path::circle(Point::new(x(i), top), 3.0),
+                    palette.destructive.base,
+                );
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Unfolded cube, with the up face above the front face.
+struct ScrambleNet {
+    cube: data::Cube,
+}
+
+impl ScrambleNet {
+    /// Position of each face in the net, in faces from the top left.
+    fn face_position(face: Face) -> (usize, usize) {
+        match face {
+            Face::U => (1, 0),
+            Face::L => (0, 1),
+            Face::F => (1, 1),
+            Face::R => (2, 1),
+            Face::B => (3, 1),
+            Face::D => (1, 2),
+        }
+    }
+
+    /// Color of the stickers of a face, in the standard color scheme.
+    fn sticker_color(face: Face) -> Color {
+        match face {
+            Face::U => Color::WHITE,
+            Face::F => Color::from_rgb8(0x00, 0x9b, 0x48),
+            Face::R => Color::from_rgb8(0xb7, 0x12, 0x34),
+            Face::D => Color::from_rgb8(0xff, 0xd5, 0x00),
+            Face::L => Color::from_rgb8(0xff, 0x58, 0x00),
+            Face::B => Color::from_rgb8(0x00, 0x46, 0xad),
+        }
+    }
+}
+
+impl canvas::Program<Message, tangible::Theme> for ScrambleNet {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        theme: &tangible::Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let n = self.cube.size();
+        let mut frame = Frame::new(bounds.size());
+        let cell = (bounds.width / (4 * n) as f32).min(bounds.height / (3 * n) as f32);
+        let origin = Point::new(
+            (bounds.width - cell * (4 * n) as f32) / 2.0,
+            (bounds.height - cell * (3 * n) as f32) / 2.0,
+        );
+        let gap = (cell * 0.08).max(1.0);
+
+        for face in Face::ALL {
+            let (face_x, face_y) = Self::face_position(face);
+            for (i, &sticker) in self.cube.face(face).iter().enumerate() {
+                let (row, col) = (i / n, i % n);
+                let top_left = Point::new(
+                    origin.x + (face_x * n + col) as f32 * cell + gap / 2.0,
+                    origin.y + (face_y * n + row) as f32 * cell + gap / 2.0,
+                );
+                let rectangle = Path::rectangle(top_left, Size::new(cell - gap, cell - gap));
+                frame.fill(&rectangle, Self::sticker_color(sticker));
+                frame.stroke(
+                    &rectangle,
+                    Stroke::default()
+                        .with_color(theme.palette().view.border)
+                        .with_width(1.0),
+                );
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
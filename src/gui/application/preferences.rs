@@ -1,15 +1,477 @@
+use std::{fmt::Display, path::PathBuf, str::FromStr, time::Duration};
+
+use iced::{alignment, keyboard::KeyCode, widget, Alignment, Application, Length};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
 use crate::tangible;
 
+use super::{
+    layout::{LayoutPreset, LayoutSize, PaneLayout},
+    shortcuts::Shortcuts,
+    KTApplication, Message,
+};
+
+/// When the timer starts counting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartTrigger {
+    /// Start when the trigger is released after being held for the hold time.
+    #[default]
+    Release,
+    /// Start as soon as the trigger is pressed.
+    Press,
+}
+
+impl StartTrigger {
+    pub const ALL: [Self; 2] = [Self::Release, Self::Press];
+}
+
+impl std::fmt::Display for StartTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Release => "Start on release",
+                Self::Press => "Start on press",
+            }
+        )
+    }
+}
+
+/// How long the trigger must be held before the timer is ready.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HoldTime(pub Duration);
+
+impl HoldTime {
+    pub const ALL: [Self; 6] = [
+        Self(Duration::ZERO),
+        Self(Duration::from_millis(100)),
+        Self(Duration::from_millis(300)),
+        Self(Duration::from_millis(500)),
+        Self(Duration::from_millis(750)),
+        Self(Duration::from_millis(1000)),
+    ];
+}
+
+impl Default for HoldTime {
+    fn default() -> Self {
+        Self(Duration::from_millis(500))
+    }
+}
+
+impl HoldTime {
+    /// Longest hold time accepted from the preferences file.
+    pub const MAX: Duration = Duration::from_secs(3);
+}
+
+impl Serialize for HoldTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0.as_millis() as u64)
+    }
+}
+
+/// Hold times are written in milliseconds.
+impl<'de> Deserialize<'de> for HoldTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hold_time = Duration::from_millis(u64::deserialize(deserializer)?);
+        if hold_time > Self::MAX {
+            return Err(serde::de::Error::custom(format!(
+                "hold time longer than {}ms",
+                Self::MAX.as_millis()
+            )));
+        }
+        Ok(Self(hold_time))
+    }
+}
+
+impl std::fmt::Display for HoldTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_zero() {
+            write!(f, "No hold")
+        } else {
+            write!(f, "Hold {:.2}s", self.0.as_secs_f32())
+        }
+    }
+}
+
+/// How the timer is displayed while a solve is being timed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerDisplay {
+    /// Show the running time down to hundredths of a second.
+    #[default]
+    Full,
+    /// Show only the whole seconds of the running time.
+    Seconds,
+    /// Show a "Solving" label instead of the running time.
+    Label,
+    /// Show nothing.
+    Hidden,
+}
+
+impl TimerDisplay {
+    pub const ALL: [Self; 4] = [Self::Full, Self::Seconds, Self::Label, Self::Hidden];
+}
+
+impl std::fmt::Display for TimerDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Full => "Show full time",
+                Self::Seconds => "Show seconds only",
+                Self::Label => "Show \"Solving\"",
+                Self::Hidden => "Hide time",
+            }
+        )
+    }
+}
+
+/// Inspection before a solve.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Inspection {
+    #[default]
+    Off,
+    /// 15 seconds of inspection, with a +2 penalty when starting after 15 seconds
+    /// and a DNF after 17 seconds, as in WCA regulations.
+    Wca,
+}
+
+impl Inspection {
+    pub const ALL: [Self; 2] = [Self::Off, Self::Wca];
+}
+
+impl std::fmt::Display for Inspection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Off => "No inspection",
+                Self::Wca => "15s inspection",
+            }
+        )
+    }
+}
+
+/// The key starting and stopping the timer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerKey {
+    #[default]
+    Space,
+    Enter,
+}
+
+impl TriggerKey {
+    pub const ALL: [Self; 2] = [Self::Space, Self::Enter];
+
+    pub fn key_code(&self) -> KeyCode {
+        match self {
+            Self::Space => KeyCode::Space,
+            Self::Enter => KeyCode::Enter,
+        }
+    }
+}
+
+impl std::fmt::Display for TriggerKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Space => "Space key",
+                Self::Enter => "Enter key",
+            }
+        )
+    }
+}
+
+/// Decimal places of the time shown by the timer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Precision {
+    #[default]
+    Hundredths,
+    Tenths,
+}
+
+impl Precision {
+    pub const ALL: [Self; 2] = [Self::Hundredths, Self::Tenths];
+
+    /// Format the fraction of a second of a duration, with its leading dot.
+    pub fn format_fraction(&self, duration: &Duration) -> String {
+        match self {
+            Self::Hundredths => format!(".{:0>2}", duration.subsec_millis() / 10),
+            Self::Tenths => format!(".{}", duration.subsec_millis() / 100),
+        }
+    }
+}
+
+impl std::fmt::Display for Precision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Hundredths => "Show hundredths",
+                Self::Tenths => "Show tenths",
+            }
+        )
+    }
+}
+
+/// How the timer shows that the trigger is held and that it is ready to start.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReadyCue {
+    /// Red while holding and green when ready.
+    #[default]
+    RedGreen,
+    /// Orange while holding and blue when ready, which color-blind people can tell apart.
+    OrangeBlue,
+    /// Red and green, with a "Hold" or "Ready" label below the time.
+    Label,
+}
+
+impl ReadyCue {
+    pub const ALL: [Self; 3] = [Self::RedGreen, Self::OrangeBlue, Self::Label];
+
+    /// Colors of the time while holding and when ready.
+    pub fn colors(&self, palette: &tangible::theme::Palette) -> (iced::Color, iced::Color) {
+        match self {
+            Self::RedGreen | Self::Label => (palette.destructive.base, palette.success.base),
+            Self::OrangeBlue => (palette.warning.base, palette.accent.base),
+        }
+    }
+}
+
+impl std::fmt::Display for ReadyCue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::RedGreen => "Red and green",
+                Self::OrangeBlue => "Orange and blue",
+                Self::Label => "Red and green with labels",
+            }
+        )
+    }
+}
+
+/// Sizes of the averages shown below the timer, sorted and without duplicates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Averages(Vec<usize>);
+
+impl Averages {
+    /// Largest number of averages shown.
+    pub const MAX_COUNT: usize = 4;
+    /// Largest size of an average.
+    pub const MAX_SIZE: usize = 1000;
+
+    /// Create a list of averages, or `None` if there are none, too many, or one
+    /// is not between 3 and [`Self::MAX_SIZE`] solves.
+    pub fn new(mut sizes: Vec<usize>) -> Option<Self> {
+        sizes.sort_unstable();
+        sizes.dedup();
+        let valid = !sizes.is_empty()
+            && sizes.len() <= Self::MAX_COUNT
+            && sizes.iter().all(|n| (3..=Self::MAX_SIZE).contains(n));
+        valid.then_some(Self(sizes))
+    }
+
+    pub fn sizes(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+impl Default for Averages {
+    fn default() -> Self {
+        Self(vec![5, 12])
+    }
+}
+
+impl Display for Averages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sizes: Vec<_> = self.0.iter().map(|n| n.to_string()).collect();
+        write!(f, "{}", sizes.join(", "))
+    }
+}
+
+/// An error which can be returned when parsing a list of averages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidAverages;
+
+impl Display for InvalidAverages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected 1 to {} sizes from 3 to {}, like \"5, 12\"",
+            Averages::MAX_COUNT,
+            Averages::MAX_SIZE
+        )
+    }
+}
+
+impl std::error::Error for InvalidAverages {}
+
+/// Averages are written as sizes separated by commas or spaces, like `5, 12, 100`.
+impl FromStr for Averages {
+    type Err = InvalidAverages;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sizes = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|size| !size.is_empty())
+            .map(|size| size.parse().map_err(|_| InvalidAverages))
+            .collect::<Result<_, _>>()?;
+        Self::new(sizes).ok_or(InvalidAverages)
+    }
+}
+
+impl<'de> Deserialize<'de> for Averages {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(Vec::deserialize(deserializer)?)
+            .ok_or_else(|| serde::de::Error::custom(InvalidAverages))
+    }
+}
+
+/// Focus mode, where the timer page shows only the scramble and the timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusMode {
+    pub enabled: bool,
+    /// Make the window fullscreen while focusing.
+    pub fullscreen: bool,
+    /// Hide the averages below the timer while focusing.
+    pub hide_averages: bool,
+}
+
+impl Default for FocusMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fullscreen: true,
+            hide_averages: false,
+        }
+    }
+}
+
+/// Settings of the application, saved in the configuration directory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Preferences {
     pub theme: tangible::Theme,
+    pub inspection: Inspection,
+    /// Hold time before the timer is ready. Zero means ready on press.
+    pub hold_time: HoldTime,
+    pub start_trigger: StartTrigger,
+    pub trigger_key: TriggerKey,
+    /// Decimal places of the time shown by the timer.
+    pub precision: Precision,
+    /// Averages shown below the timer.
+    pub averages: Averages,
+    /// How the running time is shown while timing.
+    pub timer_display: TimerDisplay,
+    /// How holding and being ready to start are shown.
+    pub ready_cue: ReadyCue,
+    /// Panels of the timer page.
+    pub layout: PaneLayout,
+    /// Panels of the timer page in small windows.
+    pub compact_layout: PaneLayout,
+    pub focus: FocusMode,
+    /// Keys of the shortcuts of the timer page.
+    pub shortcuts: Shortcuts,
 }
 
 impl Preferences {
     pub fn new() -> Self {
         Self {
             theme: tangible::Theme::Light,
+            inspection: Inspection::default(),
+            hold_time: HoldTime::default(),
+            start_trigger: StartTrigger::default(),
+            trigger_key: TriggerKey::default(),
+            precision: Precision::default(),
+            averages: Averages::default(),
+            timer_display: TimerDisplay::default(),
+            ready_cue: ReadyCue::default(),
+            layout: PaneLayout::default(),
+            compact_layout: LayoutPreset::Compact.layout(),
+            focus: FocusMode::default(),
+            shortcuts: Shortcuts::default(),
+        }
+    }
+
+    /// Read preferences written by [`Self::to_toml`], where the theme is one of `themes`.
+    ///
+    /// Missing or invalid values are replaced by their default, so that a bad value
+    /// does not reset the other preferences.
+    pub fn from_toml(text: &str, themes: &[tangible::Theme]) -> Self {
+        let table: toml::Table = match text.parse() {
+            Ok(table) => table,
+            Err(e) => {
+                log::warn!("cannot read the preferences: {}", e);
+                return Self::new();
+            }
+        };
+
+        let defaults = Self::new();
+        let theme = field::<String>(&table, "theme").and_then(|key| {
+            let theme = themes.iter().find(|theme| theme.key() == key).cloned();
+            if theme.is_none() {
+                log::warn!("unknown theme {}", key);
+            }
+            theme
+        });
+        Self {
+            theme: theme.unwrap_or(defaults.theme),
+            inspection: field(&table, "inspection").unwrap_or(defaults.inspection),
+            hold_time: field(&table, "hold_time").unwrap_or(defaults.hold_time),
+            start_trigger: field(&table, "start_trigger").unwrap_or(defaults.start_trigger),
+            trigger_key: field(&table, "trigger_key").unwrap_or(defaults.trigger_key),
+            precision: field(&table, "precision").unwrap_or(defaults.precision),
+            averages: field(&table, "averages").unwrap_or(defaults.averages),
+            timer_display: field(&table, "timer_display").unwrap_or(defaults.timer_display),
+            ready_cue: field(&table, "ready_cue").unwrap_or(defaults.ready_cue),
+            layout: field(&table, "layout").unwrap_or(defaults.layout),
+            compact_layout: field(&table, "compact_layout").unwrap_or(defaults.compact_layout),
+            focus: field(&table, "focus").unwrap_or(defaults.focus),
+            shortcuts: field(&table, "shortcuts").unwrap_or(defaults.shortcuts),
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("preferences are representable in TOML")
+    }
+
+    /// Get the path of the file the preferences are saved to.
+    fn path() -> Option<PathBuf> {
+        Some(
+            dirs::config_dir()?
+                .join("kubia-timer")
+                .join("preferences.toml"),
+        )
+    }
+
+    /// Load the saved preferences, or the default ones if none were saved.
+    pub fn load(themes: &[tangible::Theme]) -> Self {
+        match Self::path().and_then(|path| std::fs::read_to_string(path).ok()) {
+            Some(text) => Self::from_toml(&text, themes),
+            None => Self::new(),
         }
     }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::path().ok_or(std::io::ErrorKind::NotFound)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_toml())
+    }
 }
 
 impl Default for Preferences {
@@ -17,3 +479,280 @@ impl Default for Preferences {
         Self::new()
     }
 }
+
+/// Read a value of the preferences, logging why it is invalid if it is.
+fn field<T: DeserializeOwned>(table: &toml::Table, key: &str) -> Option<T> {
+    let value = table.get(key)?.clone();
+    match value.try_into() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::warn!("invalid preference {}: {}", key, e);
+            None
+        }
+    }
+}
+
+impl KTApplication {
+    pub(super) fn save_preferences(&self) {
+        if let Err(e) = self.preferences.save() {
+            log::warn!("cannot save the preferences: {}", e);
+        }
+    }
+
+    /// Page to edit the preferences.
+    pub(super) fn preferences_view(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let preferences = &self.preferences;
+        let app_theme = self.theme();
+        let palette = app_theme.palette();
+        let mut theme = widget::column![widget::row![
+            widget::pick_list(
+                self.themes(),
+                Some(preferences.theme.clone()),
+                Message::ThemeSelected,
+            )
+            .padding([4, 8])
+            .width(Length::Fill),
+            widget::button("Reload")
+                .padding([4, 8])
+                .on_press(Message::ThemesReload),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center)]
+        .spacing(4);
+        for error in &self.theme_errors {
+            theme = theme.push(widget::text(error).style(palette.error.base));
+        }
+        if let Some(dir) = super::themes::themes_dir() {
+            theme = theme.push(
+                widget::text(format!("Custom themes are read from {}", dir.display()))
+                    .style(tangible::theme::Text::Dim),
+            );
+        }
+
+        let mut averages = widget::column![widget::text_input("5, 12", &self.averages_input)
+            .on_input(Message::AveragesChanged)
+            .on_submit(Message::AveragesSubmit)
+            .padding([4, 8])]
+        .spacing(4);
+        if let Some(error) = &self.averages_error {
+            averages = averages.push(widget::text(error).style(palette.error.base));
+        }
+
+        let content = widget::column![
+            widget::text("Preferences").size(24),
+            preference_row("Theme", theme),
+            preference_row(
+                "Inspection",
+                widget::pick_list(
+                    &Inspection::ALL[..],
+                    Some(preferences.inspection),
+                    Message::InspectionSelected,
+                )
+                .padding([4, 8])
+                .width(Length::Fill),
+            ),
+            preference_row(
+                "Trigger key",
+                widget::pick_list(
+                    &TriggerKey::ALL[..],
+                    Some(preferences.trigger_key),
+                    Message::TriggerKeySelected,
+                )
+                .padding([4, 8])
+                .width(Length::Fill),
+            ),
+            preference_row(
+                "Start",
+                widget::pick_list(
+                    &StartTrigger::ALL[..],
+                    Some(preferences.start_trigger),
+                    Message::StartTriggerSelected,
+                )
+                .padding([4, 8])
+                .width(Length::Fill),
+            ),
+            preference_row(
+                "Hold time",
+                widget::pick_list(
+                    &HoldTime::ALL[..],
+                    Some(preferences.hold_time),
+                    Message::HoldTimeSelected,
+                )
+                .padding([4, 8])
+                .width(Length::Fill),
+            ),
+            preference_row(
+                "While timing",
+                widget::pick_list(
+                    &TimerDisplay::ALL[..],
+                    Some(preferences.timer_display),
+                    Message::TimerDisplaySelected,
+                )
+                .padding([4, 8])
+                .width(Length::Fill),
+            ),
+            preference_row(
+                "Ready cue",
+                widget::pick_list(
+                    &ReadyCue::ALL[..],
+                    Some(preferences.ready_cue),
+                    Message::ReadyCueSelected,
+                )
+                .padding([4, 8])
+                .width(Length::Fill),
+            ),
+            preference_row(
+                "Precision",
+                widget::pick_list(
+                    &Precision::ALL[..],
+                    Some(preferences.precision),
+                    Message::PrecisionSelected,
+                )
+                .padding([4, 8])
+                .width(Length::Fill),
+            ),
+            preference_row("Averages", averages),
+            preference_row(
+                "Layout",
+                layout_selector(LayoutSize::Regular, &preferences.layout),
+            ),
+            preference_row(
+                "Small windows",
+                layout_selector(LayoutSize::Compact, &preferences.compact_layout),
+            ),
+            preference_row(
+                "Focus mode",
+                widget::column![
+                    widget::checkbox(
+                        "Fullscreen",
+                        preferences.focus.fullscreen,
+                        Message::FocusFullscreenToggled,
+                    ),
+                    widget::checkbox(
+                        "Hide averages",
+                        preferences.focus.hide_averages,
+                        Message::FocusAveragesToggled,
+                    ),
+                ]
+                .spacing(8),
+            ),
+            preference_row("Shortcuts", self.shortcuts_selector()),
+            widget::button("Reset to defaults")
+                .padding([4, 8])
+                .style(tangible::theme::Button::Destructive)
+                .on_press(Message::PreferencesReset),
+        ]
+        .spacing(16)
+        .padding(16)
+        .align_items(Alignment::Center)
+        .max_width(500);
+
+        widget::scrollable(widget::container(content).width(Length::Fill).center_x()).into()
+    }
+}
+
+/// Presets of a layout, with the one matching the layout selected.
+fn layout_selector<'a>(
+    size: LayoutSize,
+    layout: &PaneLayout,
+) -> iced::Element<'a, Message, iced::Renderer<tangible::Theme>> {
+    let preset = LayoutPreset::ALL
+        .into_iter()
+        .find(|preset| preset.layout() == *layout);
+
+    widget::pick_list(&LayoutPreset::ALL[..], preset, move |preset| {
+        Message::LayoutPresetSelected(size, preset)
+    })
+    .placeholder("Custom")
+    .padding([4, 8])
+    .width(Length::Fill)
+    .into()
+}
+
+/// A labelled control of the preferences page.
+fn preference_row<'a>(
+    label: &str,
+    control: impl Into<iced::Element<'a, Message, iced::Renderer<tangible::Theme>>>,
+) -> iced::Element<'a, Message, iced::Renderer<tangible::Theme>> {
+    widget::row![
+        widget::text(label)
+            .width(Length::FillPortion(1))
+            .horizontal_alignment(alignment::Horizontal::Right),
+        widget::container(control).width(Length::FillPortion(2)),
+    ]
+    .spacing(16)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gui::application::layout::Panel;
+
+    #[test]
+    fn invalid_preferences_fall_back_to_defaults() {
+        let themes = tangible::Theme::ALL;
+        let preferences = Preferences::from_toml(
+            r#"
+            theme = "dark"
+            inspection = "sometimes"
+            hold_time = 300
+            trigger_key = "enter"
+            precision = 3
+            averages = [12, 5, 100, 5]
+            ready_cue = "orange-blue"
+            compact_layout = "timer"
+
+            [focus]
+            enabled = true
+
+            [layout]
+            axis = "vertical"
+            ratio = 0.5
+            a = "timer"
+            b = "timer"
+            "#,
+            &themes,
+        );
+        assert_eq!(preferences.theme, tangible::Theme::Dark);
+        assert_eq!(preferences.inspection, Inspection::Off);
+        assert_eq!(preferences.hold_time, HoldTime(Duration::from_millis(300)));
+        assert_eq!(preferences.trigger_key, TriggerKey::Enter);
+        assert_eq!(preferences.precision, Precision::Hundredths);
+        assert_eq!(preferences.averages.sizes(), [5, 12, 100]);
+        assert_eq!(preferences.ready_cue, ReadyCue::OrangeBlue);
+        assert_eq!(preferences.layout, PaneLayout::default());
+        assert_eq!(preferences.compact_layout.panels(), [Panel::Timer]);
+        assert_eq!(
+            preferences.focus,
+            FocusMode {
+                enabled: true,
+                ..FocusMode::default()
+            }
+        );
+        assert_eq!(
+            Preferences::from_toml(&preferences.to_toml(), &themes),
+            preferences
+        );
+
+        let preferences = Preferences::from_toml(
+            "theme = \"solarized\"\nhold_time = 60000\naverages = [1, 5]",
+            &themes,
+        );
+        assert_eq!(preferences.theme, tangible::Theme::Light);
+        assert_eq!(preferences.hold_time, HoldTime::default());
+        assert_eq!(preferences.averages, Averages::default());
+        assert_eq!(
+            Preferences::from_toml("not toml =", &themes),
+            Preferences::new()
+        );
+
+        assert_eq!("50 5,12".parse::<Averages>().unwrap().sizes(), [5, 12, 50]);
+        for invalid in ["", "5, x", "2", "5 12 50 100 1000"] {
+            assert!(invalid.parse::<Averages>().is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use iced::{widget, Alignment, Application, Length};
+
+use crate::{data, tangible};
+
+use super::{KTApplication, Message};
+
+impl KTApplication {
+    /// Take the next scramble of the imported queue, or generate one when the queue
+    /// is used up.
+    pub(super) fn next_scramble(&mut self) -> data::Algorithm {
+        if let Some(queue) = self.scramble_queue.as_mut() {
+            match queue.next_scramble() {
+                Some(scramble) => {
+                    if let Err(e) = queue.save() {
+                        log::warn!("cannot save the scramble queue: {}", e);
+                    }
+                    return scramble;
+                }
+                None => self.clear_scramble_queue(),
+            }
+        }
+        self.new_scramble()
+    }
+
+    /// Import a list of scrambles read from the file at `path`.
+    pub(super) fn import_scrambles(&mut self, path: &str, text: &str) {
+        let name = Path::new(path)
+            .file_name()
+            .map_or(path.into(), |name| name.to_string_lossy());
+        match data::ScrambleQueue::parse(&name, text) {
+            Ok(queue) => {
+                self.scramble_queue = Some(queue);
+                self.scramble_queue_error = None;
+                self.scramble = self.next_scramble();
+            }
+            Err(e) => self.scramble_queue_error = Some(e.to_string()),
+        }
+    }
+
+    /// Replace the scramble with the next extra scramble of the imported queue.
+    pub(super) fn use_extra_scramble(&mut self) {
+        let Some(queue) = self.scramble_queue.as_mut() else {
+            return;
+        };
+        if let Some(scramble) = queue.extra_scramble() {
+            if let Err(e) = queue.save() {
+                log::warn!("cannot save the scramble queue: {}", e);
+            }
+            self.scramble = scramble;
+        }
+    }
+
+    pub(super) fn clear_scramble_queue(&mut self) {
+        self.scramble_queue = None;
+        if let Err(e) = data::ScrambleQueue::remove_saved() {
+            log::warn!("cannot remove the saved scramble queue: {}", e);
+        }
+    }
+
+    /// Input of the path of a scramble list to import.
+    pub(super) fn scramble_queue_options(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let mut column = widget::column![widget::row![
+            widget::text_input("Scramble list (.txt or .json)", &self.scramble_file)
+                .on_input(Message::ScrambleFileChanged)
+                .on_submit(Message::ScrambleFileImport)
+                .padding([4, 8])
+                .width(Length::Fixed(260.0)),
+            widget::button("Import")
+                .padding([4, 8])
+                .on_press(Message::ScrambleFileImport),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center)]
+        .spacing(4)
+        .align_items(Alignment::Center);
+
+        if let Some(error) = &self.scramble_queue_error {
+            let theme = self.theme();
+            let palette = theme.palette();
+            column = column.push(widget::text(error).style(palette.error.base));
+        }
+
+        column.into()
+    }
+
+    /// Position in the imported scramble list, shown in the header bar.
+    pub(super) fn scramble_queue_indicator(
+        &self,
+    ) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        let queue = self.scramble_queue.as_ref()?;
+
+        let mut row = widget::row![
+            widget::text(format!("Scrambles: {}", queue)).style(tangible::theme::Text::Dim)
+        ]
+        .spacing(4)
+        .align_items(Alignment::Center);
+        if queue.extra_left() > 0 {
+            row = row.push(
+                widget::button(widget::text(format!("Extra ({})", queue.extra_left())))
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Flat)
+                    .on_press(Message::ScrambleQueueExtra),
+            );
+        }
+        row = row.push(
+            widget::button("Clear")
+                .padding([4, 8])
+                .style(tangible::theme::Button::Flat)
+                .on_press(Message::ScrambleQueueCleared),
+        );
+
+        Some(row.into())
+    }
+}
@@ -0,0 +1,105 @@
+use iced::{widget, Alignment, Application, Length};
+
+use crate::tangible::pattern::stat_row;
+use crate::{data, tangible};
+
+use super::{KTApplication, Message};
+
+impl KTApplication {
+    /// Parse the search and filter the solves again, after the search, the tag filter or
+    /// the solves of the session changed.
+    pub(super) fn update_shown_solves(&mut self) {
+        let query = self.search.parse::<data::SolveQuery>();
+        self.search_error = query.as_ref().err().map(|e| e.to_string());
+        self.search_query = query.ok().filter(|q| !q.is_empty());
+        if self.tag_filter.0.is_none() && self.search_query.is_none() {
+            self.filtered_solves = None;
+            return;
+        }
+
+        let query = self.search_query.as_ref();
+        self.filtered_solves = Some(self.session.filtered(|solve| {
+            self.tag_filter
+                .0
+                .as_ref()
+                .is_none_or(|tag| solve.has_tag(tag))
+                && query.is_none_or(|query| query.matches(solve))
+        }));
+    }
+
+    /// Get the solves shown in the sidebar and the statistics, with their statistics
+    /// recomputed if they are filtered by tag or by the search.
+    ///
+    /// The indices of the shown solves in the session are returned if they are filtered.
+    pub(super) fn shown_solves(&self) -> (&data::Session, Option<&[usize]>) {
+        match &self.filtered_solves {
+            Some((session, indices)) => (session, Some(indices)),
+            None => (&self.session, None),
+        }
+    }
+
+    /// Search bar and filters above the solves of the sidebar, with statistics of the
+    /// matching solves when searching.
+    pub(super) fn sidebar_header(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let mut column = widget::column![widget::row![
+            widget::text_input("Search, e.g. >10 <15 tag:lockup", &self.search)
+                .on_input(Message::SearchChanged)
+                .padding([4, 8])
+                .width(Length::Fill),
+            widget::button("Clear")
+                .padding([4, 8])
+                .style(tangible::theme::Button::Flat)
+                .on_press(Message::SearchChanged(String::new())),
+        ]
+        .spacing(4)
+        .align_items(Alignment::Center)]
+        .spacing(8)
+        .padding([8, 8, 0, 8])
+        .width(Length::Fill);
+
+        if let Some(e) = &self.search_error {
+            let theme = self.theme();
+            let palette = theme.palette();
+            column = column.push(widget::text(e).style(palette.error.base));
+        }
+        if let Some(selector) = self.tag_filter_selector() {
+            column = column.push(selector);
+        }
+        if self.search_query.is_some() {
+            column = column.push(self.search_results());
+        }
+
+        column.into()
+    }
+
+    /// Statistics of the solves matching the search.
+    fn search_results(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let (session, _) = self.shown_solves();
+        let display =
+            |time: Option<data::SolveTime>| time.map_or("--".to_string(), |t| t.to_string());
+        let timed = session
+            .iter()
+            .filter(|se| se.solve.is_timed())
+            .map(|se| se.solve.time);
+
+        widget::container(
+            widget::column![
+                stat_row("Solves", session.get_n_solves().to_string()),
+                stat_row("Mean", display(session.mean())),
+                stat_row("Best", display(timed.clone().min())),
+                stat_row("Worst", display(timed.filter(|t| !t.is_dnf()).max())),
+                stat_row("Best ao5", display(session.best_ao5())),
+                stat_row("Best ao12", display(session.best_ao12())),
+            ]
+            .spacing(4)
+            .align_items(Alignment::Center),
+        )
+        .style(self.theme().palette().card)
+        .padding(8)
+        .width(Length::Fill)
+        .center_x()
+        .into()
+    }
+}
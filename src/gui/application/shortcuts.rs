@@ -0,0 +1,482 @@
+use std::collections::BTreeMap;
+
+use iced::{keyboard::KeyCode, widget, Alignment, Application, Command, Length};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{data, tangible};
+
+use super::{ButtonType, GhostChoice, KTApplication, Message, TagFilter, TimerMode};
+
+/// An action of the timer page run by a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Shortcut {
+    SetOk,
+    SetPlus2,
+    SetDnf,
+    DeleteLastSolve,
+    NewScramble,
+    /// Swap the session with the last of the previous sessions.
+    SwitchSession,
+    Statistics,
+    Focus,
+    CheatSheet,
+}
+
+impl Shortcut {
+    pub const ALL: [Self; 9] = [
+        Self::SetOk,
+        Self::SetPlus2,
+        Self::SetDnf,
+        Self::DeleteLastSolve,
+        Self::NewScramble,
+        Self::SwitchSession,
+        Self::Statistics,
+        Self::Focus,
+        Self::CheatSheet,
+    ];
+
+    /// Key running the action when it is not rebound.
+    fn default_key(&self) -> Key {
+        let code = match self {
+            Self::SetOk => KeyCode::Key1,
+            Self::SetPlus2 => KeyCode::Key2,
+            Self::SetDnf => KeyCode::Key3,
+            Self::DeleteLastSolve => KeyCode::Delete,
+            Self::NewScramble => KeyCode::N,
+            Self::SwitchSession => KeyCode::S,
+            Self::Statistics => KeyCode::T,
+            Self::Focus => KeyCode::F,
+            Self::CheatSheet => KeyCode::Slash,
+        };
+        Key::from_key_code(code).expect("default keys can be bound")
+    }
+}
+
+impl std::fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::SetOk => "Remove the penalty of the last solve",
+                Self::SetPlus2 => "Give +2 to the last solve",
+                Self::SetDnf => "Mark the last solve DNF",
+                Self::DeleteLastSolve => "Delete the last solve",
+                Self::NewScramble => "New scramble",
+                Self::SwitchSession => "Switch to the previous session",
+                Self::Statistics => "Show statistics",
+                Self::Focus => "Enter or leave focus mode",
+                Self::CheatSheet => "Show keyboard shortcuts",
+            }
+        )
+    }
+}
+
+/// A key which can run a shortcut.
+///
+/// The trigger keys and Escape are not among them, as they are always used by
+/// the timer and to close dialogs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    /// Name in the preferences file.
+    name: &'static str,
+    label: &'static str,
+}
+
+impl Key {
+    pub const ALL: [Self; 62] = [
+        Self::new(KeyCode::A, "a", "A"),
+        Self::new(KeyCode::B, "b", "B"),
+        Self::new(KeyCode::C, "c", "C"),
+        Self::new(KeyCode::D, "d", "D"),
+        Self::new(KeyCode::E, "e", "E"),
+        Self::new(KeyCode::F, "f", "F"),
+        Self::new(KeyCode::G, "g", "G"),
+        Self::new(KeyCode::H, "h", "H"),
+        Self::new(KeyCode::I, "i", "I"),
+        Self::new(KeyCode::J, "j", "J"),
+        Self::new(KeyCode::K, "k", "K"),
+        Self::new(KeyCode::L, "l", "L"),
+        Self::new(KeyCode::M, "m", "M"),
+        Self::new(KeyCode::N, "n", "N"),
+        Self::new(KeyCode::O, "o", "O"),
+        Self::new(KeyCode::P, "p", "P"),
+        Self::new(KeyCode::Q, "q", "Q"),
+        Self::new(KeyCode::R, "r", "R"),
+        Self::new(KeyCode::S, "s", "S"),
+        Self::new(KeyCode::T, "t", "T"),
+        Self::new(KeyCode::U, "u", "U"),
+        Self::new(KeyCode::V, "v", "V"),
+        Self::new(KeyCode::W, "w", "W"),
+        Self::new(KeyCode::X, "x", "X"),
+        Self::new(KeyCode::Y, "y", "Y"),
+        Self::new(KeyCode::Z, "z", "Z"),
+        Self::new(KeyCode::Key0, "0", "0"),
+        Self::new(KeyCode::Key1, "1", "1"),
+        Self::new(KeyCode::Key2, "2", "2"),
+        Self::new(KeyCode::Key3, "3", "3"),
+        Self::new(KeyCode::Key4, "4", "4"),
+        Self::new(KeyCode::Key5, "5", "5"),
+        Self::new(KeyCode::Key6, "6", "6"),
+        Self::new(KeyCode::Key7, "7", "7"),
+        Self::new(KeyCode::Key8, "8", "8"),
+        Self::new(KeyCode::Key9, "9", "9"),
+        Self::new(KeyCode::F1, "f1", "F1"),
+        Self::new(KeyCode::F2, "f2", "F2"),
+        Self::new(KeyCode::F3, "f3", "F3"),
+        Self::new(KeyCode::F4, "f4", "F4"),
+        Self::new(KeyCode::F5, "f5", "F5"),
+        Self::new(KeyCode::F6, "f6", "F6"),
+        Self::new(KeyCode::F7, "f7", "F7"),
+        Self::new(KeyCode::F8, "f8", "F8"),
+        Self::new(KeyCode::F9, "f9", "F9"),
+        Self::new(KeyCode::F10, "f10", "F10"),
+        Self::new(KeyCode::F11, "f11", "F11"),
+        Self::new(KeyCode::F12, "f12", "F12"),
+        Self::new(KeyCode::Delete, "delete", "Delete"),
+        Self::new(KeyCode::Backspace, "backspace", "Backspace"),
+        Self::new(KeyCode::Insert, "insert", "Insert"),
+        Self::new(KeyCode::Home, "home", "Home"),
+        Self::new(KeyCode::End, "end", "End"),
+        Self::new(KeyCode::PageUp, "page-up", "Page Up"),
+        Self::new(KeyCode::PageDown, "page-down", "Page Down"),
+        Self::new(KeyCode::Tab, "tab", "Tab"),
+        Self::new(KeyCode::Slash, "slash", "/"),
+        Self::new(KeyCode::Comma, "comma", "Comma"),
+        Self::new(KeyCode::Period, "period", "Period"),
+        Self::new(KeyCode::Semicolon, "semicolon", "Semicolon"),
+        Self::new(KeyCode::Minus, "minus", "Minus"),
+        Self::new(KeyCode::Equals, "equals", "Equals"),
+    ];
+
+    const fn new(code: KeyCode, name: &'static str, label: &'static str) -> Self {
+        Self { code, name, label }
+    }
+
+    pub fn from_key_code(code: KeyCode) -> Option<Self> {
+        Self::ALL.into_iter().find(|key| key.code == code)
+    }
+}
+
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::ALL
+            .into_iter()
+            .find(|key| key.name == name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown key {}", name)))
+    }
+}
+
+/// Keys of every shortcut, each bound to a different key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Shortcuts(BTreeMap<Shortcut, Key>);
+
+impl Shortcuts {
+    pub fn key(&self, shortcut: Shortcut) -> Key {
+        self.0
+            .get(&shortcut)
+            .copied()
+            .unwrap_or_else(|| shortcut.default_key())
+    }
+
+    /// Get the shortcut run by a key, if any.
+    pub fn shortcut(&self, code: KeyCode) -> Option<Shortcut> {
+        Shortcut::ALL
+            .into_iter()
+            .find(|&shortcut| self.key(shortcut).code == code)
+    }
+
+    /// Bind a key to a shortcut. The shortcut which had the key, if any, takes the
+    /// previous key of `shortcut`.
+    pub fn bind(&mut self, shortcut: Shortcut, key: Key) {
+        let previous = self.key(shortcut);
+        if let Some(other) = self.shortcut(key.code) {
+            self.0.insert(other, previous);
+        }
+        self.0.insert(shortcut, key);
+    }
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        Self(
+            Shortcut::ALL
+                .into_iter()
+                .map(|shortcut| (shortcut, shortcut.default_key()))
+                .collect(),
+        )
+    }
+}
+
+/// Shortcuts missing from the preferences file keep their default key.
+impl<'de> Deserialize<'de> for Shortcuts {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let keys = BTreeMap::<Shortcut, Key>::deserialize(deserializer)?;
+        let mut shortcuts = Self::default();
+        shortcuts.0.extend(keys);
+        for shortcut in Shortcut::ALL {
+            let key = shortcuts.key(shortcut);
+            if shortcuts.shortcut(key.code) != Some(shortcut) {
+                return Err(serde::de::Error::custom(format!(
+                    "{} is bound to several shortcuts",
+                    key
+                )));
+            }
+        }
+        Ok(shortcuts)
+    }
+}
+
+/// A dialog shown over the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialog {
+    CheatSheet,
+    Statistics,
+}
+
+impl KTApplication {
+    /// Run the shortcut bound to a key, if any.
+    pub(super) fn key_shortcut(&mut self, code: KeyCode) -> Option<Command<Message>> {
+        if code == KeyCode::Escape && self.dialog.is_some() {
+            self.dialog = None;
+            return Some(Command::none());
+        }
+        let shortcut = self.preferences.shortcuts.shortcut(code)?;
+        Some(self.run_shortcut(shortcut))
+    }
+
+    /// Run a shortcut, unless a solve is being timed.
+    pub(super) fn run_shortcut(&mut self, shortcut: Shortcut) -> Command<Message> {
+        if self.timer.state.is_running() {
+            return Command::none();
+        }
+        match shortcut {
+            Shortcut::SetOk => self.set_last_penalty(None),
+            Shortcut::SetPlus2 => self.set_last_penalty(Some(data::Penalty::Plus2)),
+            Shortcut::SetDnf => self.set_last_penalty(Some(data::Penalty::Dnf)),
+            // Attempts of a round and trained cases are not in the session.
+            Shortcut::DeleteLastSolve
+                if matches!(self.mode, TimerMode::Competition | TimerMode::Trainer) => {}
+            Shortcut::DeleteLastSolve => {
+                if let Some(index) = self.session.get_n_solves().checked_sub(1) {
+                    return self.update(Message::ButtonPressed(ButtonType::DeleteSolve { index }));
+                }
+            }
+            Shortcut::NewScramble => match self.mode {
+                TimerMode::Relay => self.relay_scrambles = self.relay_set.scramble(),
+                TimerMode::Trainer => self.trainer.next_case(&mut rand::thread_rng()),
+                // The scramble of a fewest moves attempt is kept until it is submitted.
+                TimerMode::FewestMoves => {}
+                _ => self.scramble = self.next_scramble(),
+            },
+            Shortcut::SwitchSession => self.switch_session(),
+            Shortcut::Statistics => self.toggle_dialog(Dialog::Statistics),
+            Shortcut::Focus => return self.toggle_focus(),
+            Shortcut::CheatSheet => self.toggle_dialog(Dialog::CheatSheet),
+        }
+        Command::none()
+    }
+
+    /// Set the penalty of the last solve of the session, or of the last attempt of the
+    /// round in competition mode, even when another time is shown.
+    fn set_last_penalty(&mut self, penalty: Option<data::Penalty>) {
+        let time = match self.mode {
+            // Trained cases have no editable penalty.
+            TimerMode::Trainer => return,
+            TimerMode::Competition => {
+                self.round.set_last_penalty(penalty);
+                self.round.attempts().last().map(|s| s.time)
+            }
+            _ => {
+                let Some(solve) = self.session.last_solve_mut() else {
+                    return;
+                };
+                solve.time.penalty = penalty;
+                let time = solve.time;
+                self.session.update_statistics_last();
+                self.evaluate_goals();
+                self.update_shown_solves();
+                Some(time)
+            }
+        };
+        if let Some(time) = time.filter(|_| self.link_to_last_solve) {
+            self.timer.solve_time = time;
+        }
+    }
+
+    fn toggle_dialog(&mut self, dialog: Dialog) {
+        self.dialog = (self.dialog != Some(dialog)).then_some(dialog);
+    }
+
+    /// Make the last of the previous sessions the current one, keeping the current
+    /// one first among the previous sessions so that switching goes through all of them.
+    fn switch_session(&mut self) {
+        let Some(session) = self.previous_sessions.pop() else {
+            return;
+        };
+        let current = std::mem::replace(&mut self.session, session);
+        self.previous_sessions.insert(0, current);
+        // Indices of the previous sessions changed.
+        self.ghost = None;
+        self.ghost_choice = GhostChoice::None;
+        self.link_to_last_solve = false;
+        self.selected_solve = None;
+        self.deleted_solve = None;
+        self.tag_filter = TagFilter::default();
+        self.update_shown_solves();
+        if self.scramble_queue.is_none() {
+            self.scramble = self.new_scramble();
+        }
+    }
+
+    /// Contents of the dialog shown over the application.
+    pub(super) fn dialog_view(
+        &self,
+        dialog: Dialog,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let (title, content) = match dialog {
+            Dialog::CheatSheet => ("Keyboard shortcuts", self.cheat_sheet()),
+            Dialog::Statistics => ("Statistics", self.statistics_panel()),
+        };
+
+        widget::container(
+            widget::column![
+                tangible::pattern::center_row(
+                    widget::text(title).size(24),
+                    widget::button("Close")
+                        .padding([4, 8])
+                        .style(tangible::theme::Button::Flat)
+                        .on_press(Message::DialogClosed),
+                ),
+                content,
+            ]
+            .spacing(16),
+        )
+        .style(tangible::theme::Container::Solid(
+            tangible::theme::NamedColor::View,
+        ))
+        .padding(16)
+        .max_width(500)
+        .max_height(600)
+        .into()
+    }
+
+    /// Keys of the trigger and of every shortcut.
+    fn cheat_sheet(&self) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let row = |action: String, key: String| {
+            widget::row![
+                widget::text(action).width(Length::FillPortion(3)),
+                widget::text(key)
+                    .width(Length::FillPortion(1))
+                    .style(tangible::theme::Text::Dim),
+            ]
+            .spacing(16)
+            .align_items(Alignment::Center)
+            .into()
+        };
+
+        let rows = [
+            row(
+                "Start and stop the timer".to_string(),
+                self.preferences.trigger_key.to_string(),
+            ),
+            row(
+                "Close this dialog or leave focus mode".to_string(),
+                "Escape".to_string(),
+            ),
+        ];
+        let column = widget::Column::with_children(
+            rows.into_iter()
+                .chain(Shortcut::ALL.into_iter().map(|shortcut| {
+                    row(
+                        shortcut.to_string(),
+                        self.preferences.shortcuts.key(shortcut).to_string(),
+                    )
+                }))
+                .collect(),
+        )
+        .spacing(8);
+
+        widget::column![
+            column,
+            widget::text("Shortcuts can be changed in the preferences.")
+                .style(tangible::theme::Text::Dim),
+        ]
+        .spacing(16)
+        .into()
+    }
+
+    /// Key of every shortcut, which can be changed.
+    pub(super) fn shortcuts_selector(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        widget::Column::with_children(
+            Shortcut::ALL
+                .into_iter()
+                .map(|shortcut| {
+                    widget::row![
+                        widget::text(shortcut).width(Length::FillPortion(2)),
+                        widget::pick_list(
+                            &Key::ALL[..],
+                            Some(self.preferences.shortcuts.key(shortcut)),
+                            move |key| Message::ShortcutKeySelected(shortcut, key),
+                        )
+                        .padding([4, 8])
+                        .width(Length::FillPortion(1)),
+                    ]
+                    .spacing(8)
+                    .align_items(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(4)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bind_and_read_shortcuts() {
+        let mut shortcuts = Shortcuts::default();
+        for shortcut in Shortcut::ALL {
+            assert_eq!(
+                shortcuts.shortcut(shortcut.default_key().code),
+                Some(shortcut)
+            );
+        }
+
+        let n = Key::from_key_code(KeyCode::N).unwrap();
+        shortcuts.bind(Shortcut::SetOk, n);
+        assert_eq!(shortcuts.key(Shortcut::SetOk), n);
+        assert_eq!(shortcuts.key(Shortcut::NewScramble).code, KeyCode::Key1);
+
+        let table = toml::to_string(&shortcuts).unwrap();
+        assert!(table.contains("set-ok = \"n\""));
+        assert_eq!(toml::from_str::<Shortcuts>(&table).unwrap(), shortcuts);
+
+        let shortcuts: Shortcuts = toml::from_str("set-dnf = \"f5\"").unwrap();
+        assert_eq!(shortcuts.key(Shortcut::SetDnf).code, KeyCode::F5);
+        assert_eq!(shortcuts.key(Shortcut::SetOk).code, KeyCode::Key1);
+        assert!(toml::from_str::<Shortcuts>("set-dnf = \"n\"").is_err());
+        assert!(toml::from_str::<Shortcuts>("set-dnf = \"space\"").is_err());
+    }
+}
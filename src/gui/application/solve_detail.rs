@@ -0,0 +1,116 @@
+use chrono::{DateTime, Local};
+use iced::{alignment, widget, Alignment, Length};
+
+use crate::tangible;
+
+use super::{KTApplication, Message};
+
+/// Tag the solves of the sidebar and the statistics are filtered by.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TagFilter(pub Option<String>);
+
+impl std::fmt::Display for TagFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(tag) => write!(f, "Tag: {}", tag),
+            None => write!(f, "All solves"),
+        }
+    }
+}
+
+impl KTApplication {
+    /// Selector of the tag to filter the solves by, if any solve has tags.
+    pub(super) fn tag_filter_selector(
+        &self,
+    ) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        let tags = self.session.tags();
+        if tags.is_empty() && self.tag_filter.0.is_none() {
+            return None;
+        }
+
+        let filters: Vec<_> = std::iter::once(TagFilter(None))
+            .chain(tags.into_iter().map(|tag| TagFilter(Some(tag))))
+            .collect();
+        Some(
+            widget::container(
+                widget::pick_list(
+                    filters,
+                    Some(self.tag_filter.clone()),
+                    Message::TagFilterSelected,
+                )
+                .padding([4, 8])
+                .width(Length::Fill),
+            )
+            .width(Length::Fill)
+            .into(),
+        )
+    }
+
+    /// Details of a solve, with its comment and tags editable.
+    pub(super) fn solve_detail(
+        &self,
+        index: usize,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let Some(solve) = self.session.get_solve(index) else {
+            return widget::Space::new(Length::Fill, Length::Fill).into();
+        };
+
+        let tags = widget::row(
+            solve
+                .tags
+                .iter()
+                .map(|tag| {
+                    widget::button(widget::text(format!("{} ×", tag)))
+                        .padding([2, 8])
+                        .style(tangible::theme::Button::Flat)
+                        .on_press(Message::SolveTagRemove(tag.clone()))
+                        .into()
+                })
+                .collect(),
+        )
+        .spacing(4);
+
+        let content = widget::column![
+            widget::row![
+                widget::text(format!("Solve {}", index + 1))
+                    .size(20)
+                    .width(Length::Fill),
+                widget::button("Close")
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Flat)
+                    .on_press(Message::SolveDetailClosed),
+            ]
+            .align_items(Alignment::Center),
+            widget::text(solve).size(48),
+            widget::text(DateTime::<Local>::from(solve.timestamp).format("%Y-%m-%d %H:%M:%S"))
+                .style(tangible::theme::Text::Dim),
+            widget::text(&solve.scramble).horizontal_alignment(alignment::Horizontal::Center),
+            widget::text_input("Comment", &solve.comment)
+                .on_input(Message::SolveCommentChanged)
+                .padding([4, 8]),
+            tags,
+            widget::row![
+                widget::text_input("Add tag, e.g. lockup", &self.tag_input)
+                    .on_input(Message::SolveTagInputChanged)
+                    .on_submit(Message::SolveTagAdd)
+                    .padding([4, 8]),
+                widget::button("Add tag")
+                    .padding([4, 8])
+                    .on_press(Message::SolveTagAdd),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+        ]
+        .spacing(16)
+        .align_items(Alignment::Center)
+        .max_width(500);
+
+        widget::container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(16)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
@@ -0,0 +1,250 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+use iced::futures::{channel::mpsc, stream, Stream, StreamExt};
+
+use crate::tangible;
+
+/// Color scheme preferred by the desktop, as read from the
+/// `org.freedesktop.appearance.color-scheme` setting of the desktop portal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    NoPreference,
+    Dark,
+    Light,
+}
+
+impl ColorScheme {
+    /// Parse the reply of the portal to reading the setting, like `(<<uint32 1>>,)`.
+    fn from_portal_reply(reply: &str) -> Option<Self> {
+        let value = reply.split("uint32").nth(1)?;
+        let digits: String = value
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        match digits.parse::<u32>().ok()? {
+            1 => Some(Self::Dark),
+            2 => Some(Self::Light),
+            _ => Some(Self::NoPreference),
+        }
+    }
+
+    /// Parse a `SettingChanged` signal of the portal printed by `gdbus monitor`, if it is
+    /// about the color scheme.
+    fn from_setting_changed(line: &str) -> Option<Self> {
+        let (_, args) = line.split_once("org.freedesktop.portal.Settings.SettingChanged")?;
+        if !args.contains("'org.freedesktop.appearance', 'color-scheme'") {
+            return None;
+        }
+        Self::from_portal_reply(args)
+    }
+}
+
+/// Source of the appearance settings of the desktop.
+pub trait Desktop {
+    /// Read the color scheme from the desktop portal, if it is available.
+    fn portal_color_scheme(&self) -> Option<ColorScheme>;
+
+    /// Read an environment variable.
+    fn var(&self, name: &str) -> Option<String>;
+}
+
+/// The desktop the application runs in, whose portal is queried with `gdbus` from GLib.
+///
+/// Without `gdbus`, only the `GTK_THEME` environment variable is used.
+pub struct SessionDesktop;
+
+impl Desktop for SessionDesktop {
+    fn portal_color_scheme(&self) -> Option<ColorScheme> {
+        let output = Command::new("gdbus")
+            .args([
+                "call",
+                "--session",
+                "--timeout",
+                "1",
+                "--dest",
+                "org.freedesktop.portal.Desktop",
+                "--object-path",
+                "/org/freedesktop/portal/desktop",
+                "--method",
+                "org.freedesktop.portal.Settings.Read",
+                "org.freedesktop.appearance",
+                "color-scheme",
+            ])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        ColorScheme::from_portal_reply(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+/// Get the theme [`tangible::Theme::System`] resolves to on a desktop.
+///
+/// The portal setting is used if it states a preference. Otherwise, the theme is dark
+/// if the GTK theme set in `GTK_THEME` is a dark variant, like `Adwaita:dark`.
+pub fn system_theme(desktop: &impl Desktop) -> tangible::Theme {
+    theme_for(desktop.portal_color_scheme(), desktop)
+}
+
+/// Get the theme for the color scheme read from the portal, if any.
+fn theme_for(color_scheme: Option<ColorScheme>, desktop: &impl Desktop) -> tangible::Theme {
+    match color_scheme {
+        Some(ColorScheme::Dark) => return tangible::Theme::Dark,
+        Some(ColorScheme::Light) => return tangible::Theme::Light,
+        Some(ColorScheme::NoPreference) | None => {}
+    }
+
+    match desktop.var("GTK_THEME") {
+        Some(gtk_theme) if gtk_theme.to_lowercase().contains("dark") => tangible::Theme::Dark,
+        _ => tangible::Theme::Light,
+    }
+}
+
+/// A `gdbus monitor` process, killed when dropped.
+struct Monitor(Child);
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Watch the color scheme setting of the portal, producing the theme
+/// [`tangible::Theme::System`] resolves to each time the setting changes.
+///
+/// A single `gdbus monitor` process listens to the `SettingChanged` signals for as long
+/// as the stream is alive. Without `gdbus`, the stream ends at once.
+pub fn watch_system_theme() -> impl Stream<Item = tangible::Theme> {
+    let (sender, receiver) = mpsc::unbounded();
+    let child = Command::new("gdbus")
+        .args([
+            "monitor",
+            "--session",
+            "--dest",
+            "org.freedesktop.portal.Desktop",
+            "--object-path",
+            "/org/freedesktop/portal/desktop",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let monitor = match child {
+        Ok(mut child) => {
+            if let Some(stdout) = child.stdout.take() {
+                std::thread::spawn(move || {
+                    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                        let Some(color_scheme) = ColorScheme::from_setting_changed(&line) else {
+                            continue;
+                        };
+                        let theme = theme_for(Some(color_scheme), &SessionDesktop);
+                        if sender.unbounded_send(theme).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            Some(Monitor(child))
+        }
+        Err(e) => {
+            log::warn!("cannot watch the desktop color scheme: {}", e);
+            None
+        }
+    };
+
+    stream::unfold((receiver, monitor), |(mut receiver, monitor)| async move {
+        let theme = receiver.next().await?;
+        Some((theme, (receiver, monitor)))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct MockDesktop {
+        color_scheme: Option<ColorScheme>,
+        gtk_theme: Option<&'static str>,
+    }
+
+    impl Desktop for MockDesktop {
+        fn portal_color_scheme(&self) -> Option<ColorScheme> {
+            self.color_scheme
+        }
+
+        fn var(&self, name: &str) -> Option<String> {
+            match name {
+                "GTK_THEME" => self.gtk_theme.map(String::from),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_system_theme() {
+        let desktop = |color_scheme, gtk_theme| MockDesktop {
+            color_scheme,
+            gtk_theme,
+        };
+        let dark = tangible::Theme::Dark;
+        let light = tangible::Theme::Light;
+
+        assert_eq!(system_theme(&desktop(Some(ColorScheme::Dark), None)), dark);
+        assert_eq!(
+            system_theme(&desktop(Some(ColorScheme::Light), Some("Adwaita:dark"))),
+            light
+        );
+        assert_eq!(
+            system_theme(&desktop(
+                Some(ColorScheme::NoPreference),
+                Some("Adwaita:dark")
+            )),
+            dark
+        );
+        assert_eq!(system_theme(&desktop(None, Some("Arc-Dark"))), dark);
+        assert_eq!(system_theme(&desktop(None, Some("Adwaita"))), light);
+        assert_eq!(system_theme(&desktop(None, None)), light);
+
+        assert_eq!(
+            ColorScheme::from_portal_reply("(<<uint32 1>>,)\n"),
+            Some(ColorScheme::Dark)
+        );
+        assert_eq!(
+            ColorScheme::from_portal_reply("(<uint32 2>,)"),
+            Some(ColorScheme::Light)
+        );
+        assert_eq!(
+            ColorScheme::from_portal_reply("(<<uint32 0>>,)"),
+            Some(ColorScheme::NoPreference)
+        );
+        assert_eq!(ColorScheme::from_portal_reply("Error: no portal"), None);
+        assert_eq!(
+            ColorScheme::from_setting_changed(
+                "/org/freedesktop/portal/desktop: org.freedesktop.portal.Settings.SettingChanged \
+                 ('org.freedesktop.appearance', 'color-scheme', <uint32 1>)"
+            ),
+            Some(ColorScheme::Dark)
+        );
+        assert_eq!(
+            ColorScheme::from_setting_changed(
+                "/org/freedesktop/portal/desktop: org.freedesktop.portal.Settings.SettingChanged \
+                 ('org.gnome.desktop.interface', 'cursor-size', <int32 24>)"
+            ),
+            None
+        );
+
+        assert_eq!(tangible::Theme::System.resolve(dark.clone()), dark);
+        assert_eq!(
+            tangible::Theme::Tangible.resolve(dark.clone()),
+            tangible::Theme::Tangible
+        );
+    }
+}
@@ -0,0 +1,516 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use iced::{alignment, widget, Alignment, Application, Color, Length};
+use palette::{FromColor, Hsl, RgbHue, Srgb};
+
+use crate::tangible::{
+    self,
+    theme::{BackgroundColor, ColorGroup, CustomTheme, NamedColor, Palette, ThemeFileError},
+};
+
+use super::{themes, KTApplication, Message};
+
+/// A color of a group edited in the theme editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorField {
+    Base,
+    /// Background, whose hover and strong shades are derived from it.
+    Background,
+    Foreground,
+    Border,
+}
+
+impl ColorField {
+    pub const ALL: [Self; 4] = [Self::Base, Self::Background, Self::Foreground, Self::Border];
+
+    fn get(&self, group: &ColorGroup) -> Color {
+        match self {
+            Self::Base => group.base,
+            Self::Background => group.bg.base,
+            Self::Foreground => group.fg,
+            Self::Border => group.border,
+        }
+    }
+
+    fn set(&self, group: &mut ColorGroup, color: Color) {
+        match self {
+            Self::Base => group.base = color,
+            Self::Background => group.bg = BackgroundColor::derived(color),
+            Self::Foreground => group.fg = color,
+            Self::Border => group.border = color,
+        }
+    }
+}
+
+impl std::fmt::Display for ColorField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Base => "Base",
+                Self::Background => "Background",
+                Self::Foreground => "Text",
+                Self::Border => "Border",
+            }
+        )
+    }
+}
+
+/// A custom theme being edited.
+pub struct ThemeEditor {
+    pub name: String,
+    pub palette: Palette,
+    /// Text of the color inputs which are not valid colors yet.
+    drafts: HashMap<(NamedColor, ColorField), String>,
+    /// Last color picked with the sliders, which keeps the hue of grays.
+    picked: HashMap<(NamedColor, ColorField), Hsl>,
+    pub export_path: String,
+    /// Outcome of the last save or export.
+    pub status: Option<Result<String, String>>,
+}
+
+impl ThemeEditor {
+    /// Start editing a copy of a theme.
+    pub fn new(theme: &tangible::Theme) -> Self {
+        let name = match theme {
+            tangible::Theme::Custom(custom) => custom.name.clone(),
+            theme => format!("My {}", theme),
+        };
+        Self {
+            name,
+            palette: *theme.palette(),
+            drafts: HashMap::new(),
+            picked: HashMap::new(),
+            export_path: String::new(),
+            status: None,
+        }
+    }
+
+    /// Text of the input of a color.
+    pub fn input(&self, name: NamedColor, field: ColorField) -> String {
+        match self.drafts.get(&(name, field)) {
+            Some(draft) => draft.clone(),
+            None => tangible::theme::hex_color(field.get(&self.palette.group(name))),
+        }
+    }
+
+    /// Change a color from its input, once the input is a valid color.
+    pub fn set_input(&mut self, name: NamedColor, field: ColorField, input: String) {
+        match tangible::theme::parse_hex_color(&input) {
+            Some(color) => {
+                field.set(self.palette.group_mut(name), color);
+                self.drafts.remove(&(name, field));
+                self.picked.remove(&(name, field));
+            }
+            None => {
+                self.drafts.insert((name, field), input);
+            }
+        }
+    }
+
+    /// Hue, saturation and lightness of a color, as shown by the sliders.
+    pub fn hsl(&self, name: NamedColor, field: ColorField) -> Hsl {
+        match self.picked.get(&(name, field)) {
+            Some(&hsl) => hsl,
+            None => Hsl::from_color(Srgb::from(field.get(&self.palette.group(name)))),
+        }
+    }
+
+    /// Change a color from the sliders, keeping its transparency.
+    pub fn pick(&mut self, name: NamedColor, field: ColorField, hsl: Hsl) {
+        let group = self.palette.group_mut(name);
+        let color = Color {
+            a: field.get(group).a,
+            ..Srgb::from_color(hsl).into()
+        };
+        field.set(group, color);
+        self.drafts.remove(&(name, field));
+        self.picked.insert((name, field), hsl);
+    }
+
+    /// Get the edited theme, checked as if it was read from a file.
+    pub fn theme(&self) -> Result<CustomTheme, ThemeFileError> {
+        CustomTheme::from_toml(
+            &CustomTheme {
+                name: self.name.trim().to_string(),
+                palette: self.palette,
+            }
+            .to_toml(),
+        )
+    }
+}
+
+impl KTApplication {
+    /// Use the edited theme without saving it.
+    pub(super) fn apply_edited_theme(&mut self) {
+        match self.theme_editor.theme() {
+            Ok(theme) => {
+                self.preferences.theme = tangible::Theme::Custom(Arc::new(theme));
+                self.theme_editor.status = None;
+            }
+            Err(e) => self.theme_editor.status = Some(Err(e.to_string())),
+        }
+    }
+
+    /// Save the edited theme in the themes directory and use it.
+    pub(super) fn save_edited_theme(&mut self) {
+        let saved = self
+            .theme_editor
+            .theme()
+            .map_err(|e| e.to_string())
+            .and_then(|theme| {
+                let dir = themes::themes_dir().ok_or("no configuration directory")?;
+                let file_name: String = theme
+                    .name
+                    .to_lowercase()
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                    .collect();
+                let path = dir.join(file_name).with_extension("toml");
+                // Names like "My Dark" and "My-Dark" share a file: only overwrite it when it
+                // holds the same theme.
+                if let Ok(text) = std::fs::read_to_string(&path) {
+                    match tangible::theme::CustomTheme::from_toml(&text) {
+                        Ok(existing) if existing.name == theme.name => {}
+                        Ok(existing) => {
+                            return Err(format!(
+                                "{} already holds the theme \"{}\", choose another name",
+                                path.display(),
+                                existing.name
+                            ))
+                        }
+                        Err(_) => {
+                            return Err(format!(
+                                "{} already exists, choose another name",
+                                path.display()
+                            ))
+                        }
+                    }
+                }
+                std::fs::create_dir_all(&dir)
+                    .and_then(|()| std::fs::write(&path, theme.to_toml()))
+                    .map_err(|e| format!("cannot save {}: {}", path.display(), e))?;
+                Ok((theme.name, path))
+            });
+
+        match saved {
+            Ok((name, path)) => {
+                self.reload_custom_themes();
+                if let Some(theme) = self.custom_themes.iter().find(|t| t.key() == name) {
+                    self.preferences.theme = theme.clone();
+                    self.save_preferences();
+                }
+                self.theme_editor.status = Some(Ok(format!("Saved to {}", path.display())));
+            }
+            Err(e) => self.theme_editor.status = Some(Err(e)),
+        }
+    }
+
+    /// Write the edited theme to the export path, as JSON if the path ends with `.json`.
+    pub(super) fn export_edited_theme(&mut self) {
+        let path = Path::new(self.theme_editor.export_path.trim());
+        let exported = self
+            .theme_editor
+            .theme()
+            .map_err(|e| e.to_string())
+            .and_then(|theme| {
+                let text = match path.extension().and_then(|e| e.to_str()) {
+                    Some("json") => theme.to_json(),
+                    _ => theme.to_toml(),
+                };
+                std::fs::write(path, text)
+                    .map_err(|e| format!("cannot export to {}: {}", path.display(), e))
+            });
+
+        self.theme_editor.status =
+            Some(exported.map(|()| format!("Exported to {}", path.display())));
+    }
+
+    /// Page to edit a custom theme, with a preview of its colors.
+    pub(super) fn theme_editor_view(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let editor = &self.theme_editor;
+        let theme = self.theme();
+        let palette = theme.palette();
+
+        let mut actions = widget::column![
+            widget::row![
+                widget::text_input("Theme name", &editor.name)
+                    .on_input(Message::ThemeEditorNameChanged)
+                    .padding([4, 8]),
+                widget::button("Apply")
+                    .padding([4, 8])
+                    .on_press(Message::ThemeEditorApply),
+                widget::button("Save")
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Suggested)
+                    .on_press(Message::ThemeEditorSave),
+                widget::button("Start over")
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Flat)
+                    .on_press(Message::ThemeEditorReset),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+            widget::row![
+                widget::text_input("Export to (.toml or .json)", &editor.export_path)
+                    .on_input(Message::ThemeEditorExportPathChanged)
+                    .on_submit(Message::ThemeEditorExport)
+                    .padding([4, 8]),
+                widget::button("Export")
+                    .padding([4, 8])
+                    .on_press(Message::ThemeEditorExport),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+        ]
+        .spacing(8);
+        match &editor.status {
+            Some(Ok(status)) => {
+                actions = actions.push(widget::text(status).style(tangible::theme::Text::Dim))
+            }
+            Some(Err(e)) => actions = actions.push(widget::text(e).style(palette.error.base)),
+            None => {}
+        }
+        for issue in editor
+            .palette
+            .contrast_issues(tangible::theme::ContrastLevel::Aa)
+        {
+            actions = actions
+                .push(widget::text(format!("Low contrast: {}", issue)).style(palette.warning.base));
+        }
+
+        let groups = widget::Column::with_children(
+            NamedColor::ALL
+                .iter()
+                .map(|&name| color_group_editor(editor, name))
+                .collect(),
+        )
+        .spacing(16);
+
+        let content = widget::row![
+            widget::scrollable(
+                widget::column![actions, groups]
+                    .spacing(16)
+                    .padding(16)
+                    .width(Length::Fill)
+            ),
+            widget::container(preview(&editor.palette))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .padding(16)
+                .center_x()
+                .center_y(),
+        ];
+
+        content.into()
+    }
+}
+
+/// Inputs of the colors of a group.
+fn color_group_editor<'a>(
+    editor: &ThemeEditor,
+    name: NamedColor,
+) -> iced::Element<'a, Message, iced::Renderer<tangible::Theme>> {
+    let group = editor.palette.group(name);
+    let mut column = widget::column![widget::text(name).size(20)].spacing(4);
+    for field in ColorField::ALL {
+        let swatch = ColorGroup {
+            bg: BackgroundColor::new(field.get(&group)),
+            ..group
+        };
+        column = column.push(
+            widget::row![
+                widget::text(field)
+                    .width(Length::Fixed(100.0))
+                    .horizontal_alignment(alignment::Horizontal::Right),
+                widget::container(widget::Space::new(24, 24))
+                    .style(tangible::theme::Container::ColorGroup(swatch)),
+                widget::text_input("#rrggbb", &editor.input(name, field))
+                    .on_input(move |input| Message::ThemeEditorColorChanged(name, field, input))
+                    .padding([4, 8])
+                    .width(Length::Fixed(120.0)),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+        );
+
+        let hsl = editor.hsl(name, field);
+        let pick = move |hsl| Message::ThemeEditorColorPicked(name, field, hsl);
+        column = column.push(
+            widget::row![
+                widget::Space::with_width(Length::Fixed(100.0)),
+                widget::text("H"),
+                widget::slider(0.0..=360.0, hsl.hue.to_positive_degrees(), move |hue| {
+                    pick(Hsl {
+                        hue: RgbHue::from_degrees(hue),
+                        ..hsl
+                    })
+                }),
+                widget::text("S"),
+                widget::slider(0.0..=1.0, hsl.saturation, move |saturation| {
+                    pick(Hsl { saturation, ..hsl })
+                })
+                .step(0.01),
+                widget::text("L"),
+                widget::slider(0.0..=1.0, hsl.lightness, move |lightness| {
+                    pick(Hsl { lightness, ..hsl })
+                })
+                .step(0.01),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+        );
+    }
+
+    column.into()
+}
+
+/// Widgets drawn with the colors of the edited palette.
+fn preview<'a>(palette: &Palette) -> iced::Element<'a, Message, iced::Renderer<tangible::Theme>> {
+    let button = |label: &str, group: ColorGroup| {
+        widget::button(widget::text(label).horizontal_alignment(alignment::Horizontal::Center))
+            .padding([4, 8])
+            .style(tangible::theme::Button::ColorGroup(group))
+            .on_press(Message::Todo)
+    };
+    let digits = |color: Color| {
+        widget::row![
+            widget::text("12").size(64).style(color),
+            widget::text(".34").size(48).style(color),
+        ]
+        .align_items(Alignment::End)
+    };
+
+    let header = widget::container(widget::text("Header bar").style(palette.header.fg))
+        .style(tangible::theme::Container::ColorGroup(palette.header))
+        .padding(8)
+        .width(Length::Fill);
+    let selector = widget::container(
+        widget::row![
+            button("OK", palette.selector_active).width(Length::FillPortion(1)),
+            button("+2", palette.selector).width(Length::FillPortion(1)),
+            button("DNF", palette.selector).width(Length::FillPortion(1)),
+        ]
+        .spacing(4)
+        .width(Length::Fixed(200.0)),
+    )
+    .style(tangible::theme::Container::ColorGroup(palette.selector))
+    .padding(4);
+    let card = widget::container(
+        widget::column![
+            widget::text("Card").style(palette.card.fg),
+            widget::text("Warning").style(palette.warning.base),
+            widget::text("Error").style(palette.error.base),
+        ]
+        .spacing(4),
+    )
+    .style(tangible::theme::Container::ColorGroup(palette.card))
+    .padding(8)
+    .width(Length::Fill);
+
+    let content = widget::column![
+        header,
+        digits(palette.view.fg),
+        widget::row![
+            digits(palette.destructive.base),
+            digits(palette.success.base),
+        ]
+        .spacing(16),
+        selector,
+        widget::row![
+            button("Neutral", palette.neutral),
+            button("Suggested", palette.accent),
+            button("Destructive", palette.destructive),
+            button("Flat", palette.flat),
+        ]
+        .spacing(8),
+        card,
+    ]
+    .spacing(16)
+    .align_items(Alignment::Center);
+
+    widget::container(content)
+        .style(tangible::theme::Container::ColorGroup(palette.view))
+        .padding(16)
+        .max_width(500)
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edit_colors() {
+        let mut editor = ThemeEditor::new(&tangible::Theme::Dark);
+        assert_eq!(editor.name, "My Dark");
+
+        editor.set_input(
+            NamedColor::Accent,
+            ColorField::Background,
+            String::from("#12"),
+        );
+        assert_eq!(
+            editor.input(NamedColor::Accent, ColorField::Background),
+            "#12"
+        );
+        assert_eq!(
+            editor.palette.accent,
+            tangible::Theme::Dark.palette().accent
+        );
+
+        editor.set_input(
+            NamedColor::Accent,
+            ColorField::Background,
+            String::from("#123456"),
+        );
+        assert_eq!(
+            editor.input(NamedColor::Accent, ColorField::Background),
+            "#123456"
+        );
+        assert_eq!(
+            editor.palette.accent.bg,
+            BackgroundColor::derived(Color::from_rgb8(0x12, 0x34, 0x56))
+        );
+
+        let mut fg = editor.hsl(NamedColor::Window, ColorField::Foreground);
+        fg.lightness -= 0.05;
+        editor.pick(NamedColor::Window, ColorField::Foreground, fg);
+        assert_ne!(
+            editor.palette.view.fg,
+            tangible::Theme::Dark.palette().view.fg
+        );
+
+        // A gray keeps the picked hue, even though its color has none.
+        let gray = Hsl::new(200.0, 0.0, 0.5);
+        editor.pick(NamedColor::SelectorActive, ColorField::Base, gray);
+        assert_eq!(
+            editor.hsl(NamedColor::SelectorActive, ColorField::Base),
+            gray
+        );
+        editor.pick(
+            NamedColor::SelectorActive,
+            ColorField::Base,
+            Hsl {
+                saturation: 0.8,
+                ..gray
+            },
+        );
+        let selector_active = editor.theme().unwrap().palette.selector_active;
+        assert_ne!(
+            selector_active.base,
+            tangible::Theme::Dark.palette().selector_active.base
+        );
+        assert_eq!(
+            editor.input(NamedColor::SelectorActive, ColorField::Base),
+            tangible::theme::hex_color(selector_active.base)
+        );
+        assert_eq!(editor.theme().unwrap().name, "My Dark");
+
+        editor.name = String::from("Light");
+        assert!(editor.theme().is_err());
+    }
+}
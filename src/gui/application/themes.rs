@@ -0,0 +1,99 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::tangible;
+
+use super::{KTApplication, Preferences};
+
+/// Get the directory custom themes are read from.
+pub fn themes_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("kubia-timer").join("themes"))
+}
+
+/// Read the custom themes of the themes directory, from `.toml` and `.json` files.
+///
+/// Returns the themes sorted by name, and an error message for each invalid file.
+pub fn load_custom_themes() -> (Vec<tangible::Theme>, Vec<String>) {
+    let mut themes: Vec<tangible::theme::CustomTheme> = Vec::new();
+    let mut errors = Vec::new();
+
+    let Some(entries) = themes_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return (Vec::new(), errors);
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let extension = path.extension().and_then(|e| e.to_str());
+        if !matches!(extension, Some("toml" | "json")) {
+            continue;
+        }
+
+        let theme = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                let theme = match extension {
+                    Some("json") => tangible::theme::CustomTheme::from_json(&text),
+                    _ => tangible::theme::CustomTheme::from_toml(&text),
+                };
+                theme.map_err(|e| e.to_string())
+            });
+        match theme {
+            Ok(theme) if themes.contains(&theme) => {
+                errors.push(format!("{}: duplicate theme \"{}\"", file_name, theme.name))
+            }
+            Ok(theme) => themes.push(theme),
+            Err(e) => errors.push(format!("{}: {}", file_name, e)),
+        }
+    }
+
+    for error in &errors {
+        log::warn!("invalid theme file {}", error);
+    }
+    themes.sort_by_key(|theme| theme.name.to_lowercase());
+    let themes = themes
+        .into_iter()
+        .map(|theme| tangible::Theme::Custom(Arc::new(theme)))
+        .collect();
+    (themes, errors)
+}
+
+impl KTApplication {
+    /// Read the custom themes again, and use the new version of the current theme if it
+    /// is a custom one.
+    ///
+    /// If the current theme is gone, the default theme is used instead.
+    pub(super) fn reload_custom_themes(&mut self) {
+        let (custom_themes, theme_errors) = load_custom_themes();
+        self.custom_themes = custom_themes;
+        self.theme_errors = theme_errors;
+
+        if let tangible::Theme::Custom(current) = &self.preferences.theme {
+            match self
+                .custom_themes
+                .iter()
+                .find(|theme| theme.key() == current.name)
+            {
+                Some(theme) => self.preferences.theme = theme.clone(),
+                None => {
+                    self.theme_errors.push(format!(
+                        "Theme \"{}\" is gone, using the default theme",
+                        current.name
+                    ));
+                    self.preferences.theme = Preferences::new().theme;
+                }
+            }
+        }
+    }
+
+    /// Built-in themes followed by the custom ones.
+    pub(super) fn themes(&self) -> Vec<tangible::Theme> {
+        tangible::Theme::ALL
+            .iter()
+            .chain(self.custom_themes.iter())
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,235 @@
+use std::time::{Duration, Instant};
+
+use crate::data;
+
+use super::{Inspection, Preferences, StartTrigger};
+
+pub enum State {
+    Idle {
+        pressed: bool,
+    },
+    /// Inspecting the puzzle, where `holding` is `true` once the trigger is held to start.
+    Inspection {
+        holding: bool,
+    },
+    Ready,
+    Memo {
+        last_tick: Instant,
+    },
+    Timing {
+        last_tick: Instant,
+    },
+    Finished,
+}
+
+impl State {
+    /// Return `true` if the timer is ready or running.
+    pub fn is_running(&self) -> bool {
+        matches!(
+            self,
+            Self::Inspection { .. } | Self::Ready | Self::Memo { .. } | Self::Timing { .. }
+        )
+    }
+}
+
+/// Length of the inspection, after which starting the solve is penalized.
+pub const INSPECTION_TIME: Duration = Duration::from_secs(15);
+
+/// Outcome of pressing the trigger of a [`Timer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Press {
+    /// Nothing happened.
+    None,
+    /// The trigger is being held. [`Timer::timeout`] should be called after the given hold time.
+    Hold(Duration),
+    /// The timer started.
+    Start,
+    /// The memorization phase ended after the given time.
+    EndMemo(Duration),
+    /// The trigger was pressed while timing. It is up to the caller to stop the timer.
+    Stop,
+}
+
+/// A timer state machine driven by a single trigger.
+pub struct Timer {
+    pub solve_time: data::SolveTime,
+    pub state: State,
+    last_pressed: Instant,
+    trigger_down: bool,
+    /// When the inspection of the next solve started, if any.
+    inspection_started: Option<Instant>,
+}
+
+impl Timer {
+    pub fn new() -> Self {
+        Self {
+            solve_time: data::SolveTime::default(),
+            state: State::Idle { pressed: false },
+            last_pressed: Instant::now(),
+            trigger_down: false,
+            inspection_started: None,
+        }
+    }
+
+    /// Handle the trigger being pressed.
+    ///
+    /// If `memo` is `true`, a started timer begins with a memorization phase instead
+    /// of an inspection.
+    pub fn press(&mut self, preferences: &Preferences, memo: bool) -> Press {
+        let repeated = std::mem::replace(&mut self.trigger_down, true);
+        match &mut self.state {
+            State::Idle { pressed } => {
+                let start_press = !*pressed;
+                *pressed = true;
+                if !start_press {
+                    Press::None
+                } else if preferences.inspection == Inspection::Wca && !memo {
+                    self.inspection_started = Some(Instant::now());
+                    self.state = State::Inspection { holding: false };
+                    Press::None
+                } else {
+                    self.inspection_started = None;
+                    self.hold(preferences, memo)
+                }
+            }
+            State::Inspection { holding: false } if !repeated => self.hold(preferences, memo),
+            State::Memo { last_tick } if !repeated => {
+                self.state = State::Timing {
+                    last_tick: *last_tick,
+                };
+                Press::EndMemo(self.solve_time.time)
+            }
+            State::Timing { .. } if !repeated => Press::Stop,
+            _ => Press::None,
+        }
+    }
+
+    /// Start holding the trigger before the timer is ready.
+    fn hold(&mut self, preferences: &Preferences, memo: bool) -> Press {
+        if let State::Inspection { holding } = &mut self.state {
+            *holding = true;
+        }
+        if preferences.start_trigger == StartTrigger::Press {
+            self.solve_time = data::SolveTime::default();
+            self.start(memo);
+            Press::Start
+        } else if preferences.hold_time.0.is_zero() {
+            self.solve_time = data::SolveTime::default();
+            self.state = State::Ready;
+            Press::None
+        } else {
+            self.last_pressed = Instant::now();
+            Press::Hold(preferences.hold_time.0)
+        }
+    }
+
+    /// Handle the trigger being released. Returns `true` if the timer started.
+    pub fn release(&mut self, memo: bool) -> bool {
+        self.trigger_down = false;
+        match &mut self.state {
+            State::Idle { pressed } => {
+                *pressed = false;
+            }
+            State::Inspection { holding } => {
+                *holding = false;
+            }
+            State::Ready => {
+                self.start(memo);
+                return true;
+            }
+            State::Finished => {
+                self.state = State::Idle { pressed: false };
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Handle the hold time having elapsed after the trigger was pressed.
+    pub fn timeout(&mut self, preferences: &Preferences) {
+        if let State::Idle { pressed: true } | State::Inspection { holding: true } = self.state {
+            if self.last_pressed.elapsed() >= preferences.hold_time.0 {
+                self.solve_time = data::SolveTime::default();
+                self.state = State::Ready;
+            }
+        }
+    }
+
+    /// Advance the running time.
+    pub fn tick(&mut self, now: Instant) {
+        match &mut self.state {
+            State::Memo { last_tick } | State::Timing { last_tick } => {
+                self.solve_time.time += now - *last_tick;
+                *last_tick = now;
+            }
+            _ => {}
+        }
+    }
+
+    /// Start the timer, with a memorization phase if `memo` is `true`.
+    ///
+    /// Starting after an inspection longer than [`INSPECTION_TIME`] is penalized.
+    pub fn start(&mut self, memo: bool) {
+        if let Some(started) = self.inspection_started.take() {
+            let overtime = started.elapsed().saturating_sub(INSPECTION_TIME);
+            self.solve_time.penalty = if overtime.is_zero() {
+                None
+            } else if overtime <= Duration::from_secs(2) {
+                Some(data::Penalty::Plus2)
+            } else {
+                Some(data::Penalty::Dnf)
+            };
+        }
+        let last_tick = Instant::now();
+        self.state = match memo {
+            true => State::Memo { last_tick },
+            false => State::Timing { last_tick },
+        };
+    }
+
+    /// Stop the timer.
+    pub fn stop(&mut self) {
+        self.state = State::Finished;
+    }
+
+    /// Return `true` if the timer needs to be ticked, that is while holding, inspecting
+    /// or running.
+    pub fn is_ticking(&self) -> bool {
+        matches!(
+            self.state,
+            State::Idle { pressed: true }
+                | State::Inspection { .. }
+                | State::Memo { .. }
+                | State::Timing { .. }
+        )
+    }
+
+    /// Time elapsed since the inspection started, until the timer starts.
+    pub fn inspection_time(&self) -> Option<Duration> {
+        Some(self.inspection_started?.elapsed())
+    }
+
+    /// Progress of the hold before the timer is ready, from 0.0 to 1.0.
+    ///
+    /// Returns `None` if no hold is in progress or holding is not required.
+    pub fn hold_progress(&self, preferences: &Preferences) -> Option<f32> {
+        let hold_time = preferences.hold_time.0;
+        if preferences.start_trigger == StartTrigger::Press || hold_time.is_zero() {
+            return None;
+        }
+
+        match self.state {
+            State::Idle { pressed: true } | State::Inspection { holding: true } => {
+                Some((self.last_pressed.elapsed().as_secs_f32() / hold_time.as_secs_f32()).min(1.0))
+            }
+            State::Ready => Some(1.0),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
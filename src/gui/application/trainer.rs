@@ -0,0 +1,110 @@
+use iced::{alignment, widget, Alignment, Length};
+
+use crate::tangible::pattern::stat_row;
+use crate::{data, tangible};
+
+use super::{KTApplication, Message};
+
+impl KTApplication {
+    /// Buttons to choose the case set and to mark the last case as misrecognized.
+    pub(super) fn trainer_options(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let mut row = widget::row![widget::pick_list(
+            &data::CaseSet::ALL[..],
+            Some(self.trainer.set),
+            Message::CaseSetSelected,
+        )
+        .padding([4, 8])]
+        .spacing(8)
+        .align_items(Alignment::Center);
+
+        if self.trainer.can_mark_mistake() {
+            row = row.push(
+                widget::button("Recognition mistake")
+                    .padding([4, 8])
+                    .style(tangible::theme::Button::Destructive)
+                    .on_press(Message::RecognitionMistake),
+            );
+        }
+
+        row.into()
+    }
+
+    /// The last trained case with its solution and statistics.
+    pub(super) fn trainer_summary(
+        &self,
+    ) -> Option<iced::Element<'_, Message, iced::Renderer<tangible::Theme>>> {
+        let case = self.trainer.last()?;
+        let index = self.trainer.set.cases().iter().position(|c| c == case)?;
+        let stats = self.trainer.stats(index);
+
+        Some(
+            widget::column![
+                widget::text(case.name).size(20),
+                widget::text(format!(
+                    "{}: {}",
+                    self.trainer.set.solution_label(),
+                    case.alg
+                ))
+                .style(tangible::theme::Text::Dim),
+                stat_row(
+                    "Case mean",
+                    stats.mean().map_or("--".to_string(), |m| m.to_string()),
+                ),
+                stat_row("Mistakes", stats.mistakes.to_string()),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center)
+            .into(),
+        )
+    }
+
+    /// Cases of the set, which can be selected for training, with their statistics.
+    pub(super) fn trainer_sidebar(
+        &self,
+    ) -> iced::Element<'_, Message, iced::Renderer<tangible::Theme>> {
+        let cases_column = widget::Column::with_children(
+            self.trainer
+                .set
+                .cases()
+                .iter()
+                .enumerate()
+                .map(|(i, case)| {
+                    let stats = self.trainer.stats(i);
+                    widget::row![
+                        widget::checkbox(case.name, self.trainer.is_selected(i), move |selected| {
+                            Message::CaseToggled(i, selected)
+                        })
+                        .width(Length::FillPortion(2)),
+                        widget::text(stats.times.len())
+                            .horizontal_alignment(alignment::Horizontal::Center)
+                            .width(Length::FillPortion(1)),
+                        widget::text(stats.mean().map_or("--".to_string(), |m| m.to_string()))
+                            .horizontal_alignment(alignment::Horizontal::Center)
+                            .width(Length::FillPortion(1)),
+                        widget::text(stats.mistakes)
+                            .horizontal_alignment(alignment::Horizontal::Center)
+                            .width(Length::FillPortion(1)),
+                    ]
+                    .spacing(4)
+                    .align_items(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(4)
+        .padding(8)
+        .align_items(Alignment::Start)
+        .width(Length::Fill);
+
+        widget::scrollable(cases_column)
+            .vertical_scroll(
+                widget::scrollable::Properties::new()
+                    .width(4.0)
+                    .margin(4.0)
+                    .scroller_width(4.0),
+            )
+            .into()
+    }
+}
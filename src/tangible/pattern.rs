@@ -1,9 +1,34 @@
-// pub fn center_row<'a, Message, Renderer>(
-//     start: Option<impl Into<iced::Element<'a, Message, Renderer>>>,
-//     end: Option<impl Into<iced::Element<'a, Message, Renderer>>>,
-// ) -> iced::widget::Row<'a, Message, Renderer> 
-// where 
-//     Renderer: iced_native::Renderer,
-// {
-//     let row iced::widget::row!() {}
-// }
+use iced::{alignment, widget, Alignment, Element, Length};
+
+use super::Theme;
+
+/// A row with `start` on the left and `end` on the right of the available width.
+pub fn center_row<'a, Message: 'a>(
+    start: impl Into<Element<'a, Message, iced::Renderer<Theme>>>,
+    end: impl Into<Element<'a, Message, iced::Renderer<Theme>>>,
+) -> widget::Row<'a, Message, iced::Renderer<Theme>> {
+    widget::row![
+        start.into(),
+        widget::horizontal_space(Length::Fill),
+        end.into()
+    ]
+    .align_items(Alignment::Center)
+}
+
+/// A statistic with its label on the left and its value on the right.
+pub fn stat_row<'a, Message: 'a>(
+    label: &str,
+    value: String,
+) -> Element<'a, Message, iced::Renderer<Theme>> {
+    widget::row![
+        widget::text(label)
+            .width(Length::FillPortion(1))
+            .horizontal_alignment(alignment::Horizontal::Right),
+        widget::text(value)
+            .width(Length::FillPortion(1))
+            .horizontal_alignment(alignment::Horizontal::Left),
+    ]
+    .spacing(8)
+    .width(Length::Fixed(200.0))
+    .into()
+}
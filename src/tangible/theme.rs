@@ -1,27 +1,73 @@
+pub use self::contrast::{
+    composite, contrast_ratio, relative_luminance, ContrastIssue, ContrastLevel,
+};
+pub use self::custom::{hex_color, parse_hex_color, CustomTheme, ThemeFileError};
 pub use self::palette::{
-    ColorGroup, NamedColor, Palette, PALETTE_DARK, PALETTE_LIGHT, PALETTE_TANGIBLE,
+    darken, lighten, BackgroundColor, ColorGroup, NamedColor, Palette, PALETTE_DARK,
+    PALETTE_HIGH_CONTRAST, PALETTE_LIGHT, PALETTE_TANGIBLE,
 };
 
+use std::sync::Arc;
+
 use iced::{overlay, widget, Color, Vector};
+use serde::{Serialize, Serializer};
 
+mod contrast;
+mod custom;
 mod palette;
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub enum Theme {
     #[default]
     Light,
     Dark,
     Tangible,
+    /// White on black with bright colors, meeting WCAG AAA contrast.
+    HighContrast,
+    /// Light or dark following the desktop. See [`Theme::resolve`].
+    System,
+    /// A theme read from a file.
+    Custom(Arc<CustomTheme>),
 }
 
 impl Theme {
-    pub const ALL: [Self; 3] = [Self::Light, Self::Dark, Self::Tangible];
+    pub const ALL: [Self; 5] = [
+        Self::Light,
+        Self::Dark,
+        Self::Tangible,
+        Self::HighContrast,
+        Self::System,
+    ];
+
+    /// Get the name identifying the theme in files, like `light` or the name of a
+    /// custom theme.
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::Tangible => "tangible",
+            Self::HighContrast => "high-contrast",
+            Self::System => "system",
+            Self::Custom(custom) => &custom.name,
+        }
+    }
+
+    /// Get the theme to draw with, which is `system` for [`Theme::System`].
+    pub fn resolve(self, system: Theme) -> Theme {
+        match self {
+            Self::System => system,
+            theme => theme,
+        }
+    }
 
+    /// Get the colors of the theme. An unresolved [`Theme::System`] is light.
     pub fn palette(&self) -> &Palette {
         match self {
-            Self::Light => &palette::PALETTE_LIGHT,
+            Self::Light | Self::System => &palette::PALETTE_LIGHT,
             Self::Dark => &palette::PALETTE_DARK,
             Self::Tangible => &palette::PALETTE_TANGIBLE,
+            Self::HighContrast => &palette::PALETTE_HIGH_CONTRAST,
+            Self::Custom(custom) => &custom.palette,
         }
     }
 }
@@ -35,11 +81,20 @@ impl std::fmt::Display for Theme {
                 Self::Light => "Light",
                 Self::Dark => "Dark",
                 Self::Tangible => "Tangible",
+                Self::HighContrast => "High contrast",
+                Self::System => "System",
+                Self::Custom(custom) => &custom.name,
             }
         )
     }
 }
 
+impl Serialize for Theme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.key())
+    }
+}
+
 #[derive(Default)]
 pub enum Application {
     #[default]
@@ -275,16 +330,47 @@ pub enum Slider {
 impl widget::slider::StyleSheet for Theme {
     type Style = Slider;
 
-    fn active(&self, style: &Self::Style) -> widget::vertical_slider::Appearance {
-        todo!()
+    fn active(&self, _style: &Self::Style) -> widget::vertical_slider::Appearance {
+        let palette = self.palette();
+
+        widget::vertical_slider::Appearance {
+            rail: widget::slider::Rail {
+                colors: (palette.accent.bg.base, palette.neutral.bg.strong),
+                width: 4.0,
+            },
+            handle: widget::slider::Handle {
+                shape: widget::slider::HandleShape::Circle { radius: 8.0 },
+                color: palette.card.bg.base,
+                border_width: 2.0,
+                border_color: palette.accent.bg.base,
+            },
+        }
     }
 
     fn hovered(&self, style: &Self::Style) -> widget::vertical_slider::Appearance {
-        todo!()
+        let palette = self.palette();
+        let active = self.active(style);
+
+        widget::vertical_slider::Appearance {
+            handle: widget::slider::Handle {
+                color: palette.card.bg.hover,
+                ..active.handle
+            },
+            ..active
+        }
     }
 
     fn dragging(&self, style: &Self::Style) -> widget::vertical_slider::Appearance {
-        todo!()
+        let palette = self.palette();
+        let active = self.active(style);
+
+        widget::vertical_slider::Appearance {
+            handle: widget::slider::Handle {
+                color: palette.accent.bg.base,
+                ..active.handle
+            },
+            ..active
+        }
     }
 }
 
@@ -366,12 +452,29 @@ pub enum Radio {
 impl widget::radio::StyleSheet for Theme {
     type Style = Radio;
 
-    fn active(&self, style: &Self::Style, is_selected: bool) -> widget::radio::Appearance {
-        todo!()
+    fn active(&self, _style: &Self::Style, is_selected: bool) -> widget::radio::Appearance {
+        let palette = self.palette();
+
+        widget::radio::Appearance {
+            background: palette.neutral.bg.base.into(),
+            dot_color: palette.accent.bg.base,
+            border_width: 1.0,
+            border_color: if is_selected {
+                palette.accent.bg.base
+            } else {
+                palette.neutral.bg.strong
+            },
+            text_color: None,
+        }
     }
 
     fn hovered(&self, style: &Self::Style, is_selected: bool) -> widget::radio::Appearance {
-        todo!()
+        let palette = self.palette();
+
+        widget::radio::Appearance {
+            background: palette.neutral.bg.hover.into(),
+            ..self.active(style, is_selected)
+        }
     }
 }
 
@@ -384,12 +487,34 @@ pub enum Toggler {
 impl widget::toggler::StyleSheet for Theme {
     type Style = Toggler;
 
-    fn active(&self, style: &Self::Style, is_active: bool) -> widget::toggler::Appearance {
-        todo!()
+    fn active(&self, _style: &Self::Style, is_active: bool) -> widget::toggler::Appearance {
+        let palette = self.palette();
+
+        let (background, foreground) = if is_active {
+            (palette.accent.bg.base, palette.accent.fg)
+        } else {
+            (palette.neutral.bg.hover, palette.card.bg.base)
+        };
+
+        widget::toggler::Appearance {
+            background,
+            background_border: None,
+            foreground,
+            foreground_border: None,
+        }
     }
 
     fn hovered(&self, style: &Self::Style, is_active: bool) -> widget::toggler::Appearance {
-        todo!()
+        let palette = self.palette();
+
+        widget::toggler::Appearance {
+            background: if is_active {
+                palette.accent.bg.hover
+            } else {
+                palette.neutral.bg.strong
+            },
+            ..self.active(style, is_active)
+        }
     }
 }
 
@@ -402,12 +527,18 @@ pub enum PaneGrid {
 impl widget::pane_grid::StyleSheet for Theme {
     type Style = PaneGrid;
 
-    fn picked_split(&self, style: &Self::Style) -> Option<widget::pane_grid::Line> {
-        todo!()
+    fn picked_split(&self, _style: &Self::Style) -> Option<widget::pane_grid::Line> {
+        Some(widget::pane_grid::Line {
+            color: self.palette().accent.bg.base,
+            width: 2.0,
+        })
     }
 
-    fn hovered_split(&self, style: &Self::Style) -> Option<widget::pane_grid::Line> {
-        todo!()
+    fn hovered_split(&self, _style: &Self::Style) -> Option<widget::pane_grid::Line> {
+        Some(widget::pane_grid::Line {
+            color: self.palette().accent.bg.hover,
+            width: 2.0,
+        })
     }
 }
 
@@ -424,7 +555,20 @@ impl widget::progress_bar::StyleSheet for Theme {
     type Style = ProgressBar;
 
     fn appearance(&self, style: &Self::Style) -> widget::progress_bar::Appearance {
-        todo!()
+        let palette = self.palette();
+
+        let cg = match style {
+            ProgressBar::Accent => palette.accent,
+            ProgressBar::Success => palette.success,
+            ProgressBar::Warning => palette.warning,
+            ProgressBar::Error => palette.error,
+        };
+
+        widget::progress_bar::Appearance {
+            background: palette.neutral.bg.base.into(),
+            bar: cg.bg.base.into(),
+            border_radius: 6.0,
+        }
     }
 }
 
@@ -549,7 +693,7 @@ impl widget::text::StyleSheet for Theme {
             Text::Default => Default::default(),
             Text::Dim => {
                 let mut color = self.palette().view.fg;
-                color.a = color.a * 0.5;
+                color.a *= 0.5;
                 widget::text::Appearance { color: Some(color) }
             }
             Text::Color(c) => widget::text::Appearance { color: Some(c) },
@@ -566,35 +710,153 @@ pub enum TextInput {
 impl widget::text_input::StyleSheet for Theme {
     type Style = TextInput;
 
-    fn active(&self, style: &Self::Style) -> widget::text_input::Appearance {
-        todo!()
+    fn active(&self, _style: &Self::Style) -> widget::text_input::Appearance {
+        let palette = self.palette();
+
+        widget::text_input::Appearance {
+            background: palette.card.bg.base.into(),
+            border_radius: 6.0,
+            border_width: 1.0,
+            border_color: palette.card.border,
+            icon_color: palette.card.fg,
+        }
     }
 
     fn focused(&self, style: &Self::Style) -> widget::text_input::Appearance {
-        todo!()
+        let palette = self.palette();
+
+        widget::text_input::Appearance {
+            border_width: 2.0,
+            border_color: palette.accent.bg.base,
+            ..self.active(style)
+        }
     }
 
-    fn placeholder_color(&self, style: &Self::Style) -> Color {
-        todo!()
+    fn placeholder_color(&self, _style: &Self::Style) -> Color {
+        let mut color = self.palette().card.fg;
+        color.a *= 0.5;
+        color
     }
 
-    fn value_color(&self, style: &Self::Style) -> Color {
-        todo!()
+    fn value_color(&self, _style: &Self::Style) -> Color {
+        self.palette().card.fg
     }
 
     fn disabled_color(&self, style: &Self::Style) -> Color {
-        todo!()
+        self.placeholder_color(style)
     }
 
-    fn selection_color(&self, style: &Self::Style) -> Color {
-        todo!()
+    fn selection_color(&self, _style: &Self::Style) -> Color {
+        let mut color = self.palette().accent.bg.base;
+        color.a *= 0.5;
+        color
     }
 
     fn disabled(&self, style: &Self::Style) -> widget::text_input::Appearance {
-        todo!()
+        let palette = self.palette();
+
+        widget::text_input::Appearance {
+            background: palette.view.bg.base.into(),
+            ..self.active(style)
+        }
     }
 
     fn hovered(&self, style: &Self::Style) -> widget::text_input::Appearance {
-        self.focused(style)
+        let palette = self.palette();
+
+        widget::text_input::Appearance {
+            border_color: palette.card.fg,
+            ..self.active(style)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn styles_of_every_theme() {
+        use widget::{
+            button, checkbox, container, pane_grid, pick_list, progress_bar, radio, rule,
+            scrollable, slider, text, text_input, toggler,
+        };
+
+        let custom = CustomTheme::from_toml("name = \"Custom\"\nextends = \"dark\"").unwrap();
+        let themes = Theme::ALL
+            .into_iter()
+            .chain([Theme::Custom(Arc::new(custom))]);
+
+        for theme in themes {
+            iced::application::StyleSheet::appearance(&theme, &Application::Default);
+
+            let buttons = [
+                Button::Default,
+                Button::Suggested,
+                Button::Destructive,
+                Button::Flat,
+                Button::ColorGroup(theme.palette().accent),
+            ];
+            for style in &buttons {
+                button::StyleSheet::active(&theme, style);
+                button::StyleSheet::hovered(&theme, style);
+                button::StyleSheet::pressed(&theme, style);
+                button::StyleSheet::disabled(&theme, style);
+            }
+            for style in &[Checkbox::Default, Checkbox::Success, Checkbox::Destructive] {
+                for checked in [false, true] {
+                    checkbox::StyleSheet::active(&theme, style, checked);
+                    checkbox::StyleSheet::hovered(&theme, style, checked);
+                }
+            }
+            container::StyleSheet::appearance(&theme, &Container::Transparent);
+            for name in NamedColor::ALL {
+                container::StyleSheet::appearance(&theme, &Container::Solid(name));
+                container::StyleSheet::appearance(&theme, &theme.palette().group(name).into());
+            }
+
+            slider::StyleSheet::active(&theme, &Slider::Default);
+            slider::StyleSheet::hovered(&theme, &Slider::Default);
+            slider::StyleSheet::dragging(&theme, &Slider::Default);
+            for selected in [false, true] {
+                radio::StyleSheet::active(&theme, &Radio::Default, selected);
+                radio::StyleSheet::hovered(&theme, &Radio::Default, selected);
+                toggler::StyleSheet::active(&theme, &Toggler::Default, selected);
+                toggler::StyleSheet::hovered(&theme, &Toggler::Default, selected);
+            }
+            assert!(pane_grid::StyleSheet::picked_split(&theme, &PaneGrid::Default).is_some());
+            assert!(pane_grid::StyleSheet::hovered_split(&theme, &PaneGrid::Default).is_some());
+
+            let progress_bars = [
+                ProgressBar::Accent,
+                ProgressBar::Success,
+                ProgressBar::Warning,
+                ProgressBar::Error,
+            ];
+            for style in &progress_bars {
+                progress_bar::StyleSheet::appearance(&theme, style);
+            }
+
+            overlay::menu::StyleSheet::appearance(&theme, &Menu::Default);
+            pick_list::StyleSheet::active(&theme, &PickList::Default);
+            pick_list::StyleSheet::hovered(&theme, &PickList::Default);
+            rule::StyleSheet::appearance(&theme, &Rule::Default);
+            scrollable::StyleSheet::active(&theme, &Scrollable::Default);
+            scrollable::StyleSheet::hovered(&theme, &Scrollable::Default, true);
+            scrollable::StyleSheet::dragging(&theme, &Scrollable::Default);
+            for style in [Text::Default, Text::Dim, Text::Color(Color::BLACK)] {
+                text::StyleSheet::appearance(&theme, style);
+            }
+
+            let style = &TextInput::Default;
+            text_input::StyleSheet::active(&theme, style);
+            text_input::StyleSheet::focused(&theme, style);
+            text_input::StyleSheet::hovered(&theme, style);
+            text_input::StyleSheet::disabled(&theme, style);
+            text_input::StyleSheet::placeholder_color(&theme, style);
+            text_input::StyleSheet::value_color(&theme, style);
+            text_input::StyleSheet::disabled_color(&theme, style);
+            text_input::StyleSheet::selection_color(&theme, style);
+        }
     }
 }
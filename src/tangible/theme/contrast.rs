@@ -0,0 +1,139 @@
+use iced::Color;
+
+use super::palette::{ColorGroup, Palette};
+
+/// Level of conformance to the WCAG contrast requirements for text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContrastLevel {
+    /// Contrast of at least 4.5:1.
+    Aa,
+    /// Contrast of at least 7:1.
+    Aaa,
+}
+
+impl ContrastLevel {
+    /// Lowest contrast ratio of text conforming to the level.
+    pub fn min_ratio(&self) -> f32 {
+        match self {
+            Self::Aa => 4.5,
+            Self::Aaa => 7.0,
+        }
+    }
+}
+
+/// A color group whose text does not contrast enough with its background.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastIssue {
+    /// Name of the color group in the palette, like `accent`.
+    pub group: &'static str,
+    /// Which background the text is on: `base`, `hover` or `strong`.
+    pub background: &'static str,
+    pub ratio: f32,
+}
+
+impl std::fmt::Display for ContrastIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} text on {} background has a contrast of {:.2}:1",
+            self.group, self.background, self.ratio
+        )
+    }
+}
+
+/// Get the relative luminance of an opaque color, as defined by WCAG.
+pub fn relative_luminance(color: Color) -> f32 {
+    let linear = |c: f32| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.r) + 0.7152 * linear(color.g) + 0.0722 * linear(color.b)
+}
+
+/// Blend a translucent color over an opaque one.
+pub fn composite(color: Color, under: Color) -> Color {
+    let blend = |c: f32, u: f32| c * color.a + u * (1.0 - color.a);
+    Color::from_rgb(
+        blend(color.r, under.r),
+        blend(color.g, under.g),
+        blend(color.b, under.b),
+    )
+}
+
+/// Get the contrast ratio of two opaque colors, from 1 to 21.
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+impl Palette {
+    /// Groups of the palette with their name, as they are drawn.
+    pub fn named_groups(&self) -> [(&'static str, ColorGroup); 12] {
+        [
+            ("neutral", self.neutral),
+            ("accent", self.accent),
+            ("destructive", self.destructive),
+            ("success", self.success),
+            ("warning", self.warning),
+            ("error", self.error),
+            ("flat", self.flat),
+            ("view", self.view),
+            ("card", self.card),
+            ("header", self.header),
+            ("selector", self.selector),
+            ("selector_active", self.selector_active),
+        ]
+    }
+
+    /// Check the contrast of the text of every group on its backgrounds.
+    ///
+    /// Translucent colors are drawn over the background of the window, which is the
+    /// `view` group.
+    pub fn contrast_issues(&self, level: ContrastLevel) -> Vec<ContrastIssue> {
+        let window = self.view.bg.base;
+        let mut issues = Vec::new();
+        for (group, cg) in self.named_groups() {
+            let backgrounds = [
+                ("base", cg.bg.base),
+                ("hover", cg.bg.hover),
+                ("strong", cg.bg.strong),
+            ];
+            for (background, bg) in backgrounds {
+                let bg = composite(bg, window);
+                let ratio = contrast_ratio(composite(cg.fg, bg), bg);
+                if ratio < level.min_ratio() {
+                    issues.push(ContrastIssue {
+                        group,
+                        background,
+                        ratio,
+                    });
+                }
+            }
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tangible::Theme;
+
+    #[test]
+    fn builtin_palettes_meet_aa() {
+        assert!((contrast_ratio(Color::BLACK, Color::WHITE) - 21.0).abs() < 0.001);
+        assert_eq!(contrast_ratio(Color::WHITE, Color::WHITE), 1.0);
+
+        for theme in Theme::ALL {
+            let issues = theme.palette().contrast_issues(ContrastLevel::Aa);
+            assert_eq!(issues, [], "{} does not meet AA", theme);
+        }
+        let issues = Theme::HighContrast
+            .palette()
+            .contrast_issues(ContrastLevel::Aaa);
+        assert_eq!(issues, [], "high contrast does not meet AAA");
+    }
+}
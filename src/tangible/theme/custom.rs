@@ -0,0 +1,398 @@
+use std::fmt::Display;
+
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+use super::palette::{darken, lighten, BackgroundColor, ColorGroup, Palette};
+use super::Theme;
+
+/// A theme defined by the user in a TOML or JSON file.
+///
+/// A theme file has a `name`, optionally the built-in theme it `extends` (`light` by
+/// default), and a table for each color group of [`Palette`] to change, like:
+///
+/// ```toml
+/// name = "Ocean"
+/// extends = "dark"
+///
+/// [accent]
+/// base = "#62a0ea"
+/// bg = "#1c71d8"
+/// fg = "#ffffff"
+///
+/// [card]
+/// bg = { base = "#1e2b38", hover = "lighten(0.1)", strong = "#2c4055" }
+/// ```
+///
+/// Colors are written `#rrggbb` or `#rrggbbaa`. A background given as a single color
+/// gets hover and strong shades derived from it, and each shade may be derived from the
+/// background color with `darken(amount)` or `lighten(amount)`, with an amount between
+/// 0 and 1. Groups and colors left out are taken from the extended theme.
+#[derive(Debug, Clone)]
+pub struct CustomTheme {
+    pub name: String,
+    pub palette: Palette,
+}
+
+/// Custom themes are identified by their name.
+impl PartialEq for CustomTheme {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CustomTheme {}
+
+/// An error which can be returned when reading a theme file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeFileError {
+    /// The file is not valid TOML or JSON, or has an unknown key.
+    Syntax(String),
+    /// The theme has no name, or the name of a built-in theme.
+    InvalidName(String),
+    /// The theme extends a theme which is not built in.
+    UnknownBase(String),
+    /// A color is neither a color like `#3584e4` nor a shade like `darken(0.1)`.
+    InvalidColor { key: String, value: String },
+}
+
+impl Display for ThemeFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax(e) => write!(f, "{}", e),
+            Self::InvalidName(name) => write!(f, "invalid theme name \"{}\"", name),
+            Self::UnknownBase(base) => write!(f, "cannot extend unknown theme \"{}\"", base),
+            Self::InvalidColor { key, value } => write!(f, "invalid color {} = \"{}\"", key, value),
+        }
+    }
+}
+
+impl std::error::Error for ThemeFileError {}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extends: Option<String>,
+    neutral: Option<GroupFile>,
+    accent: Option<GroupFile>,
+    destructive: Option<GroupFile>,
+    success: Option<GroupFile>,
+    warning: Option<GroupFile>,
+    error: Option<GroupFile>,
+    flat: Option<GroupFile>,
+    view: Option<GroupFile>,
+    card: Option<GroupFile>,
+    header: Option<GroupFile>,
+    selector: Option<GroupFile>,
+    selector_active: Option<GroupFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GroupFile {
+    base: Option<String>,
+    bg: Option<BackgroundFile>,
+    fg: Option<String>,
+    border: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum BackgroundFile {
+    Color(String),
+    Shades(ShadesFile),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShadesFile {
+    base: String,
+    hover: Option<String>,
+    strong: Option<String>,
+}
+
+impl CustomTheme {
+    pub fn from_toml(text: &str) -> Result<Self, ThemeFileError> {
+        let file = toml::from_str(text).map_err(|e| ThemeFileError::Syntax(e.to_string()))?;
+        Self::from_file(file)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ThemeFileError> {
+        let file = serde_json::from_str(text).map_err(|e| ThemeFileError::Syntax(e.to_string()))?;
+        Self::from_file(file)
+    }
+
+    fn from_file(file: ThemeFile) -> Result<Self, ThemeFileError> {
+        let name = file.name.trim().to_string();
+        let is_builtin = Theme::ALL
+            .iter()
+            .any(|theme| theme.to_string().eq_ignore_ascii_case(&name));
+        if name.is_empty() || is_builtin {
+            return Err(ThemeFileError::InvalidName(name));
+        }
+
+        let base = match file.extends.as_deref() {
+            None => Theme::Light.palette(),
+            Some(extends) => Theme::ALL
+                .iter()
+                .find(|theme| **theme != Theme::System && theme.key() == extends)
+                .ok_or_else(|| ThemeFileError::UnknownBase(extends.to_string()))?
+                .palette(),
+        };
+
+        let palette = Palette {
+            neutral: group("neutral", file.neutral, base.neutral)?,
+            accent: group("accent", file.accent, base.accent)?,
+            destructive: group("destructive", file.destructive, base.destructive)?,
+            success: group("success", file.success, base.success)?,
+            warning: group("warning", file.warning, base.warning)?,
+            error: group("error", file.error, base.error)?,
+            flat: group("flat", file.flat, base.flat)?,
+            view: group("view", file.view, base.view)?,
+            card: group("card", file.card, base.card)?,
+            header: group("header", file.header, base.header)?,
+            selector: group("selector", file.selector, base.selector)?,
+            selector_active: group(
+                "selector_active",
+                file.selector_active,
+                base.selector_active,
+            )?,
+        };
+
+        Ok(Self { name, palette })
+    }
+
+    /// Write the theme in the format read by [`Self::from_toml`], with every color.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(&self.to_file()).expect("themes are representable in TOML")
+    }
+
+    /// Write the theme in the format read by [`Self::from_json`], with every color.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_file()).expect("themes are representable in JSON")
+    }
+
+    fn to_file(&self) -> ThemeFile {
+        let group = |cg: ColorGroup| {
+            Some(GroupFile {
+                base: Some(hex_color(cg.base)),
+                bg: Some(BackgroundFile::Shades(ShadesFile {
+                    base: hex_color(cg.bg.base),
+                    hover: Some(hex_color(cg.bg.hover)),
+                    strong: Some(hex_color(cg.bg.strong)),
+                })),
+                fg: Some(hex_color(cg.fg)),
+                border: Some(hex_color(cg.border)),
+            })
+        };
+        let palette = &self.palette;
+
+        ThemeFile {
+            name: self.name.clone(),
+            extends: None,
+            neutral: group(palette.neutral),
+            accent: group(palette.accent),
+            destructive: group(palette.destructive),
+            success: group(palette.success),
+            warning: group(palette.warning),
+            error: group(palette.error),
+            flat: group(palette.flat),
+            view: group(palette.view),
+            card: group(palette.card),
+            header: group(palette.header),
+            selector: group(palette.selector),
+            selector_active: group(palette.selector_active),
+        }
+    }
+}
+
+/// Format a color as `#rrggbb`, or `#rrggbbaa` if it is translucent.
+pub fn hex_color(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    match a {
+        255 => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        _ => format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a),
+    }
+}
+
+/// Parse a color written `#rrggbb` or `#rrggbbaa`.
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = match hex.len() {
+        8 => channel(6)? as f32 / 255.0,
+        _ => 1.0,
+    };
+    Some(Color::from_rgba8(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+        alpha,
+    ))
+}
+
+/// Build a color group from its table in a theme file, completed by `base`.
+fn group(
+    key: &str,
+    file: Option<GroupFile>,
+    base: ColorGroup,
+) -> Result<ColorGroup, ThemeFileError> {
+    let Some(file) = file else {
+        return Ok(base);
+    };
+    let color = |field: &str, value: Option<String>, default: Color| match value {
+        Some(value) => parse_color(&format!("{}.{}", key, field), &value),
+        None => Ok(default),
+    };
+
+    let bg = match file.bg {
+        None => base.bg,
+        Some(BackgroundFile::Color(value)) => {
+            BackgroundColor::derived(parse_color(&format!("{}.bg", key), &value)?)
+        }
+        Some(BackgroundFile::Shades(ShadesFile {
+            base: bg_base,
+            hover,
+            strong,
+        })) => {
+            let bg_base = parse_color(&format!("{}.bg.base", key), &bg_base)?;
+            let derived = BackgroundColor::derived(bg_base);
+            BackgroundColor {
+                base: bg_base,
+                hover: match hover {
+                    Some(hover) => parse_shade(&format!("{}.bg.hover", key), &hover, bg_base)?,
+                    None => derived.hover,
+                },
+                strong: match strong {
+                    Some(strong) => parse_shade(&format!("{}.bg.strong", key), &strong, bg_base)?,
+                    None => derived.strong,
+                },
+            }
+        }
+    };
+
+    Ok(ColorGroup {
+        base: color("base", file.base, base.base)?,
+        bg,
+        fg: color("fg", file.fg, base.fg)?,
+        border: color("border", file.border, base.border)?,
+    })
+}
+
+/// Parse a color of a theme file.
+fn parse_color(key: &str, value: &str) -> Result<Color, ThemeFileError> {
+    parse_hex_color(value).ok_or_else(|| ThemeFileError::InvalidColor {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Parse a color, or a shade of `base` written `darken(amount)` or `lighten(amount)`.
+fn parse_shade(key: &str, value: &str, base: Color) -> Result<Color, ThemeFileError> {
+    let value = value.trim();
+    let shade = |function: &str| -> Option<f32> {
+        let amount = value
+            .strip_prefix(function)?
+            .strip_prefix('(')?
+            .strip_suffix(')')?
+            .trim()
+            .parse::<f32>()
+            .ok()?;
+        (0.0..=1.0).contains(&amount).then_some(amount)
+    };
+
+    if let Some(amount) = shade("darken") {
+        Ok(darken(base, amount))
+    } else if let Some(amount) = shade("lighten") {
+        Ok(lighten(base, amount))
+    } else if value.starts_with("darken") || value.starts_with("lighten") {
+        Err(ThemeFileError::InvalidColor {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    } else {
+        parse_color(key, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_theme_files() {
+        let theme = CustomTheme::from_toml(
+            r##"
+            name = "Ocean"
+            extends = "dark"
+
+            [accent]
+            base = "#62a0ea"
+            bg = "#1c71d8"
+
+            [card]
+            bg = { base = "#1e2b38", hover = "lighten(0.1)", strong = "#2c405580" }
+            "##,
+        )
+        .unwrap();
+        let dark = Theme::Dark.palette();
+        assert_eq!(theme.name, "Ocean");
+        assert_eq!(
+            theme.palette.accent.base,
+            Color::from_rgb8(0x62, 0xa0, 0xea)
+        );
+        assert_eq!(
+            theme.palette.accent.bg,
+            BackgroundColor::derived(Color::from_rgb8(0x1c, 0x71, 0xd8))
+        );
+        assert_eq!(theme.palette.accent.fg, dark.accent.fg);
+        assert_eq!(
+            theme.palette.card.bg.hover,
+            lighten(Color::from_rgb8(0x1e, 0x2b, 0x38), 0.1)
+        );
+        assert_eq!(
+            theme.palette.card.bg.strong,
+            Color::from_rgba8(0x2c, 0x40, 0x55, 128.0 / 255.0)
+        );
+        assert_eq!(theme.palette.view, dark.view);
+
+        let toml = theme.to_toml();
+        let reread = CustomTheme::from_toml(&toml).unwrap();
+        assert_eq!(reread.to_toml(), toml);
+        assert_eq!(reread.palette.accent.base, theme.palette.accent.base);
+        assert_eq!(
+            CustomTheme::from_json(&theme.to_json()).unwrap().to_toml(),
+            toml
+        );
+
+        let json = CustomTheme::from_json(r##"{"name": "Paper", "view": {"fg": "#000000"}}"##);
+        assert_eq!(json.unwrap().palette.view.fg, Color::BLACK);
+
+        assert_eq!(
+            CustomTheme::from_toml("name = \"dark\""),
+            Err(ThemeFileError::InvalidName(String::from("dark")))
+        );
+        assert_eq!(
+            CustomTheme::from_toml("name = \"A\"\nextends = \"solarized\""),
+            Err(ThemeFileError::UnknownBase(String::from("solarized")))
+        );
+        assert!(matches!(
+            CustomTheme::from_toml("name = \"A\"\n[accent]\nfg = \"blue\""),
+            Err(ThemeFileError::InvalidColor { key, .. }) if key == "accent.fg"
+        ));
+        assert!(matches!(
+            CustomTheme::from_toml("name = \"A\"\n[card]\nbg = { base = \"#000000\", hover = \"darken(2)\" }"),
+            Err(ThemeFileError::InvalidColor { key, .. }) if key == "card.bg.hover"
+        ));
+        assert!(matches!(
+            CustomTheme::from_toml("name = \"A\"\n[accent]\ncolour = \"#000000\""),
+            Err(ThemeFileError::Syntax(_))
+        ));
+    }
+}
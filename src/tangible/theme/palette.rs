@@ -2,7 +2,7 @@ use iced::{color, Color};
 use once_cell::sync::Lazy;
 use palette::{FromColor, Hsl, Srgb};
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct BackgroundColor {
     pub base: Color,
     pub hover: Color,
@@ -18,6 +18,19 @@ impl BackgroundColor {
         }
     }
 
+    /// Create a background with hover and strong shades derived from its base color.
+    ///
+    /// The hover shade moves away from the text color expected on the background,
+    /// dark on light colors and light on darker colors, to keep the text readable.
+    pub fn derived(base: Color) -> Self {
+        let lightness = Hsl::from_color(Srgb::from(base)).lightness;
+        if lightness > 0.6 {
+            Self::new(base).lighten_hover(0.05).darken_strong(0.1)
+        } else {
+            Self::new(base).darken_hover(0.05).darken_strong(0.1)
+        }
+    }
+
     pub fn darken_hover(self, amount: f32) -> Self {
         Self {
             hover: darken(self.base, amount),
@@ -47,7 +60,7 @@ impl BackgroundColor {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct ColorGroup {
     pub base: Color,
     pub bg: BackgroundColor,
@@ -75,7 +88,7 @@ pub struct ColorGroup {
 //     }
 // }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum NamedColor {
     Neutral,
     Accent,
@@ -87,9 +100,11 @@ pub enum NamedColor {
     Window,
     View,
     Header,
+    Selector,
+    SelectorActive,
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Palette {
     pub neutral: ColorGroup,
     pub accent: ColorGroup,
@@ -107,7 +122,64 @@ pub struct Palette {
     pub selector_active: ColorGroup,
 }
 
+impl NamedColor {
+    pub const ALL: [Self; 12] = [
+        Self::Neutral,
+        Self::Accent,
+        Self::Destructive,
+        Self::Success,
+        Self::Warning,
+        Self::Error,
+        Self::Flat,
+        Self::Window,
+        Self::View,
+        Self::Header,
+        Self::Selector,
+        Self::SelectorActive,
+    ];
+}
+
+impl std::fmt::Display for NamedColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Neutral => "Neutral",
+                Self::Accent => "Accent",
+                Self::Destructive => "Destructive",
+                Self::Success => "Success",
+                Self::Warning => "Warning",
+                Self::Error => "Error",
+                Self::Flat => "Flat",
+                Self::Window => "Window",
+                Self::View => "View",
+                Self::Header => "Header",
+                Self::Selector => "Selector",
+                Self::SelectorActive => "Active selector",
+            }
+        )
+    }
+}
+
 impl Palette {
+    pub fn group_mut(&mut self, name: NamedColor) -> &mut ColorGroup {
+        match name {
+            NamedColor::Neutral => &mut self.neutral,
+            NamedColor::Accent => &mut self.accent,
+            NamedColor::Destructive => &mut self.destructive,
+            NamedColor::Success => &mut self.success,
+            NamedColor::Warning => &mut self.warning,
+            NamedColor::Error => &mut self.error,
+            NamedColor::Flat => &mut self.flat,
+            NamedColor::Window => &mut self.view,
+            NamedColor::View => &mut self.card,
+            NamedColor::Header => &mut self.header,
+            NamedColor::Selector => &mut self.selector,
+            NamedColor::SelectorActive => &mut self.selector_active,
+        }
+    }
+
     pub fn group(&self, name: NamedColor) -> ColorGroup {
         match name {
             NamedColor::Neutral => self.neutral,
@@ -120,11 +192,13 @@ impl Palette {
             NamedColor::Window => self.view,
             NamedColor::View => self.card,
             NamedColor::Header => self.header,
+            NamedColor::Selector => self.selector,
+            NamedColor::SelectorActive => self.selector_active,
         }
     }
 }
 
-fn darken(color: Color, amount: f32) -> Color {
+pub fn darken(color: Color, amount: f32) -> Color {
     let mut hsl = Hsl::from_color(Srgb::from(color));
 
     hsl.lightness = (hsl.lightness - amount).max(0.0);
@@ -132,7 +206,7 @@ fn darken(color: Color, amount: f32) -> Color {
     Srgb::from_color(hsl).into()
 }
 
-fn lighten(color: Color, amount: f32) -> Color {
+pub fn lighten(color: Color, amount: f32) -> Color {
     let mut hsl = Hsl::from_color(Srgb::from(color));
 
     hsl.lightness = (hsl.lightness + amount).min(1.0);
@@ -145,16 +219,16 @@ pub static PALETTE_LIGHT: Lazy<Palette> = Lazy::new(|| Palette {
         base: color!(0x000000, 0.3),
         bg: BackgroundColor {
             base: color!(0x000000, 0.3),
-            hover: color!(0x000000, 0.45),
-            strong: color!(0x000000, 0.60),
+            hover: color!(0x000000, 0.38),
+            strong: color!(0x000000, 0.45),
         },
         fg: color!(0x202020),
         border: color!(0x202020, 0.2),
     },
     accent: ColorGroup {
         base: color!(0x1c71d8),
-        bg: BackgroundColor::new(color!(0x3584e4))
-            .lighten_hover(0.1)
+        bg: BackgroundColor::new(color!(0x1c71d8))
+            .darken_hover(0.05)
             .darken_strong(0.1),
         fg: color!(0xffffff),
         border: color!(0xffffff, 0.08),
@@ -162,15 +236,15 @@ pub static PALETTE_LIGHT: Lazy<Palette> = Lazy::new(|| Palette {
     destructive: ColorGroup {
         base: color!(0xc01c28),
         bg: BackgroundColor::new(color!(0xe01b24))
-            .lighten_hover(0.1)
+            .darken_hover(0.05)
             .darken_strong(0.1),
         fg: color!(0xffffff),
         border: color!(0xffffff, 0.08),
     },
     success: ColorGroup {
         base: color!(0x1b8553),
-        bg: BackgroundColor::new(color!(0x2ec27e))
-            .lighten_hover(0.1)
+        bg: BackgroundColor::new(color!(0x1b8553))
+            .darken_hover(0.05)
             .darken_strong(0.1),
         fg: color!(0xffffff),
         border: color!(0xffffff, 0.08),
@@ -178,15 +252,15 @@ pub static PALETTE_LIGHT: Lazy<Palette> = Lazy::new(|| Palette {
     warning: ColorGroup {
         base: color!(0x9c6e03),
         bg: BackgroundColor::new(color!(0xe5a50a))
-            .darken_hover(0.1)
-            .darken_strong(0.2),
+            .lighten_hover(0.05)
+            .darken_strong(0.1),
         fg: color!(0x202020),
         border: color!(0x202020, 0.2),
     },
     error: ColorGroup {
         base: color!(0xc01c28),
         bg: BackgroundColor::new(color!(0xe01b24))
-            .lighten_hover(0.1)
+            .darken_hover(0.05)
             .darken_strong(0.1),
         fg: color!(0xffffff),
         border: color!(0xffffff, 0.08),
@@ -225,8 +299,8 @@ pub static PALETTE_LIGHT: Lazy<Palette> = Lazy::new(|| Palette {
         base: color!(0x000000, 0.3),
         bg: BackgroundColor {
             base: color!(0x000000, 0.3),
-            hover: color!(0x000000, 0.45),
-            strong: color!(0x000000, 0.60),
+            hover: color!(0x000000, 0.38),
+            strong: color!(0x000000, 0.45),
         },
         fg: color!(0x202020),
         border: color!(0x202020, 0.2),
@@ -252,8 +326,8 @@ pub static PALETTE_DARK: Lazy<Palette> = Lazy::new(|| Palette {
     },
     accent: ColorGroup {
         base: color!(0x78aeed),
-        bg: BackgroundColor::new(color!(0x3584e4))
-            .lighten_hover(0.1)
+        bg: BackgroundColor::new(color!(0x1c71d8))
+            .darken_hover(0.05)
             .darken_strong(0.1),
         fg: color!(0xffffff),
         border: color!(0xffffff, 0.08),
@@ -268,8 +342,8 @@ pub static PALETTE_DARK: Lazy<Palette> = Lazy::new(|| Palette {
     },
     success: ColorGroup {
         base: color!(0x8ff0a4),
-        bg: BackgroundColor::new(color!(0x26a269))
-            .lighten_hover(0.1)
+        bg: BackgroundColor::new(color!(0x1b8553))
+            .darken_hover(0.05)
             .darken_strong(0.1),
         fg: color!(0xffffff),
         border: color!(0xffffff, 0.08),
@@ -277,8 +351,8 @@ pub static PALETTE_DARK: Lazy<Palette> = Lazy::new(|| Palette {
     warning: ColorGroup {
         base: color!(0xf8e45c),
         bg: BackgroundColor::new(color!(0xcd9309))
-            .darken_hover(0.1)
-            .darken_strong(0.2),
+            .lighten_hover(0.05)
+            .darken_strong(0.05),
         fg: color!(0x202020),
         border: color!(0x202020, 0.2),
     },
@@ -351,8 +425,8 @@ pub static PALETTE_TANGIBLE: Lazy<Palette> = Lazy::new(|| Palette {
     },
     accent: ColorGroup {
         base: color!(0x78aeed),
-        bg: BackgroundColor::new(color!(0x3584e4))
-            .lighten_hover(0.1)
+        bg: BackgroundColor::new(color!(0x1c71d8))
+            .darken_hover(0.05)
             .darken_strong(0.1),
         fg: color!(0xffffff),
         border: color!(0xffffff, 0.08),
@@ -367,8 +441,8 @@ pub static PALETTE_TANGIBLE: Lazy<Palette> = Lazy::new(|| Palette {
     },
     success: ColorGroup {
         base: color!(0x8ff0a4),
-        bg: BackgroundColor::new(color!(0x26a269))
-            .lighten_hover(0.1)
+        bg: BackgroundColor::new(color!(0x1b8553))
+            .darken_hover(0.05)
             .darken_strong(0.1),
         fg: color!(0xffffff),
         border: color!(0xffffff, 0.08),
@@ -376,8 +450,8 @@ pub static PALETTE_TANGIBLE: Lazy<Palette> = Lazy::new(|| Palette {
     warning: ColorGroup {
         base: color!(0xf8e45c),
         bg: BackgroundColor::new(color!(0xcd9309))
-            .darken_hover(0.1)
-            .darken_strong(0.2),
+            .lighten_hover(0.05)
+            .darken_strong(0.05),
         fg: color!(0x202020),
         border: color!(0x202020, 0.2),
     },
@@ -446,3 +520,109 @@ pub static PALETTE_TANGIBLE: Lazy<Palette> = Lazy::new(|| Palette {
         border: color!(0x000000),
     },
 });
+
+/// A palette of white text on black with bright accents, for low vision.
+pub static PALETTE_HIGH_CONTRAST: Lazy<Palette> = Lazy::new(|| Palette {
+    neutral: ColorGroup {
+        base: color!(0xffffff),
+        bg: BackgroundColor {
+            base: color!(0x1a1a1a),
+            hover: color!(0x333333),
+            strong: color!(0x4d4d4d),
+        },
+        fg: color!(0xffffff),
+        border: color!(0xffffff),
+    },
+    accent: ColorGroup {
+        base: color!(0x8cc8ff),
+        bg: BackgroundColor::new(color!(0x8cc8ff))
+            .lighten_hover(0.05)
+            .darken_strong(0.1),
+        fg: color!(0x000000),
+        border: color!(0xffffff),
+    },
+    destructive: ColorGroup {
+        base: color!(0xff9e9e),
+        bg: BackgroundColor::new(color!(0xff9e9e))
+            .lighten_hover(0.05)
+            .darken_strong(0.1),
+        fg: color!(0x000000),
+        border: color!(0xffffff),
+    },
+    success: ColorGroup {
+        base: color!(0x7cf07c),
+        bg: BackgroundColor::new(color!(0x7cf07c))
+            .lighten_hover(0.05)
+            .darken_strong(0.1),
+        fg: color!(0x000000),
+        border: color!(0xffffff),
+    },
+    warning: ColorGroup {
+        base: color!(0xffd700),
+        bg: BackgroundColor::new(color!(0xffd700))
+            .lighten_hover(0.05)
+            .darken_strong(0.1),
+        fg: color!(0x000000),
+        border: color!(0xffffff),
+    },
+    error: ColorGroup {
+        base: color!(0xff9e9e),
+        bg: BackgroundColor::new(color!(0xff9e9e))
+            .lighten_hover(0.05)
+            .darken_strong(0.1),
+        fg: color!(0x000000),
+        border: color!(0xffffff),
+    },
+    flat: ColorGroup {
+        base: color!(0x000000, 0.0),
+        bg: BackgroundColor {
+            base: color!(0xffffff, 0.0),
+            hover: color!(0xffffff, 0.15),
+            strong: color!(0xffffff, 0.25),
+        },
+        fg: color!(0xffffff),
+        border: color!(0xffffff),
+    },
+    view: ColorGroup {
+        base: color!(0x000000),
+        bg: BackgroundColor::new(color!(0x000000)).lighten_strong(0.1),
+        fg: color!(0xffffff),
+        border: color!(0xffffff),
+    },
+    card: ColorGroup {
+        base: color!(0x000000),
+        bg: BackgroundColor {
+            base: color!(0x000000),
+            hover: color!(0x262626),
+            strong: color!(0x333333),
+        },
+        fg: color!(0xffffff),
+        border: color!(0xffffff),
+    },
+    header: ColorGroup {
+        base: color!(0x000000),
+        bg: BackgroundColor::new(color!(0x000000)).lighten_strong(0.1),
+        fg: color!(0xffffff),
+        border: color!(0xffffff),
+    },
+    selector: ColorGroup {
+        base: color!(0xffffff),
+        bg: BackgroundColor {
+            base: color!(0x1a1a1a),
+            hover: color!(0x333333),
+            strong: color!(0x4d4d4d),
+        },
+        fg: color!(0xffffff),
+        border: color!(0xffffff),
+    },
+    selector_active: ColorGroup {
+        base: color!(0xffffff),
+        bg: BackgroundColor {
+            base: color!(0xffffff),
+            hover: color!(0xe6e6e6),
+            strong: color!(0xcccccc),
+        },
+        fg: color!(0x000000),
+        border: color!(0xffffff),
+    },
+});
@@ -0,0 +1,722 @@
+use iced::{alignment, widget, Alignment, Color, Element, Length};
+use iced_native::{
+    event, layout, mouse, overlay, renderer,
+    widget::{Operation, Tree},
+    Clipboard, Event, Layout, Point, Rectangle, Shell, Size, Vector, Widget,
+};
+
+use super::{
+    pattern,
+    theme::{self, ColorGroup},
+    Palette, Theme,
+};
+
+/// A time in big digits, with the fraction of a second in smaller digits.
+///
+/// `whole` is the time down to the second, like `1:02`, and `fraction` is the rest,
+/// like `.34`.
+pub fn time_display<'a, Message: 'a>(
+    whole: impl ToString,
+    fraction: impl ToString,
+    size: f32,
+    color: Color,
+) -> widget::Row<'a, Message, iced::Renderer<Theme>> {
+    widget::row![
+        widget::text(whole).style(color).size(size),
+        widget::text(fraction).style(color).size(size * 0.75),
+    ]
+    .align_items(Alignment::End)
+}
+
+/// A row of buttons choosing one of a few values, like the penalty of a solve.
+///
+/// The buttons share the width of the selector, which is set by the caller.
+pub fn segmented<'a, T, L, Message>(
+    segments: impl IntoIterator<Item = (L, T)>,
+    selected: &T,
+    on_select: impl Fn(T) -> Message,
+    palette: &Palette,
+) -> widget::Container<'a, Message, iced::Renderer<Theme>>
+where
+    T: PartialEq,
+    L: ToString,
+    Message: Clone + 'a,
+{
+    let mut row = widget::Row::new()
+        .spacing(4)
+        .align_items(Alignment::Center)
+        .width(Length::Fill);
+    for (label, value) in segments {
+        let style = if value == *selected {
+            palette.selector_active
+        } else {
+            palette.selector
+        };
+        row = row.push(
+            widget::button(widget::text(label).horizontal_alignment(alignment::Horizontal::Center))
+                .style(style.into())
+                .padding(4)
+                .on_press(on_select(value))
+                .width(Length::FillPortion(1)),
+        );
+    }
+
+    widget::container(row).style(palette.selector).padding(4)
+}
+
+/// A bar at the top of a window, with `start` on the left and `end` on the right.
+pub fn headerbar<'a, Message: 'a>(
+    start: impl Into<Element<'a, Message, iced::Renderer<Theme>>>,
+    end: impl Into<Element<'a, Message, iced::Renderer<Theme>>>,
+) -> Element<'a, Message, iced::Renderer<Theme>> {
+    widget::column![
+        widget::container(pattern::center_row(start, end).spacing(4))
+            .style(theme::Container::Solid(theme::NamedColor::Header))
+            .padding(4)
+            .width(Length::Fill),
+        widget::horizontal_rule(1),
+    ]
+    .into()
+}
+
+/// A notification with a button dismissing it, to be shown over content with [`Toasts`].
+pub fn toast<'a, Message: Clone + 'a>(
+    text: impl ToString,
+    color: ColorGroup,
+    on_dismiss: Message,
+) -> Element<'a, Message, iced::Renderer<Theme>> {
+    toast_container(
+        widget::row![
+            widget::text(text),
+            widget::button("Dismiss")
+                .padding([4, 8])
+                .style(color.into())
+                .on_press(on_dismiss),
+        ],
+        color,
+    )
+}
+
+/// A notification with an action, like undoing what it notifies of, before the button
+/// dismissing it.
+pub fn action_toast<'a, Message: Clone + 'a>(
+    text: impl ToString,
+    color: ColorGroup,
+    action: &'a str,
+    on_action: Message,
+    on_dismiss: Message,
+) -> Element<'a, Message, iced::Renderer<Theme>> {
+    toast_container(
+        widget::row![
+            widget::text(text),
+            widget::button(action)
+                .padding([4, 8])
+                .style(color.into())
+                .on_press(on_action),
+            widget::button("Dismiss")
+                .padding([4, 8])
+                .style(theme::Button::Flat)
+                .on_press(on_dismiss),
+        ],
+        color,
+    )
+}
+
+fn toast_container<'a, Message: 'a>(
+    row: widget::Row<'a, Message, iced::Renderer<Theme>>,
+    color: ColorGroup,
+) -> Element<'a, Message, iced::Renderer<Theme>> {
+    widget::container(row.spacing(8).align_items(Alignment::Center))
+        .style(color)
+        .padding([4, 4, 4, 12])
+        .max_width(500)
+        .into()
+}
+
+/// Content with notifications stacked over its bottom.
+///
+/// Only the notifications take events over the content, so that it stays usable
+/// while they are shown.
+pub struct Toasts<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    toasts: Vec<Element<'a, Message, Renderer>>,
+}
+
+impl<'a, Message, Renderer> Toasts<'a, Message, Renderer> {
+    /// Show notifications, usually made with [`toast`], over some content.
+    pub fn new(
+        content: impl Into<Element<'a, Message, Renderer>>,
+        toasts: Vec<Element<'a, Message, Renderer>>,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            toasts,
+        }
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for Toasts<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        std::iter::once(&self.content)
+            .chain(&self.toasts)
+            .map(Tree::new)
+            .collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let children: Vec<_> = std::iter::once(&self.content).chain(&self.toasts).collect();
+        tree.diff_children(&children);
+    }
+
+    fn draw(
+        &self,
+        state: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &state.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+    }
+
+    fn operate(
+        &self,
+        state: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut state.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        state: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            &mut state.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &state.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        state: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        let (content_state, toasts_state) = state.children.split_at_mut(1);
+        let content = self
+            .content
+            .as_widget_mut()
+            .overlay(&mut content_state[0], layout, renderer);
+        let toasts = (!self.toasts.is_empty()).then(|| {
+            overlay::Element::new(
+                layout.position(),
+                Box::new(ToastsOverlay {
+                    size: layout.bounds().size(),
+                    toasts: &mut self.toasts,
+                    state: toasts_state,
+                }),
+            )
+        });
+
+        let overlays: Vec<_> = content.into_iter().chain(toasts).collect();
+        (!overlays.is_empty()).then(|| overlay::Group::with_children(overlays).overlay())
+    }
+}
+
+impl<'a, Message, Renderer> From<Toasts<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(toasts: Toasts<'a, Message, Renderer>) -> Self {
+        Element::new(toasts)
+    }
+}
+
+/// The notifications of [`Toasts`], centered at the bottom of the content.
+struct ToastsOverlay<'a, 'b, Message, Renderer> {
+    /// Size of the content.
+    size: Size,
+    toasts: &'b mut [Element<'a, Message, Renderer>],
+    state: &'b mut [Tree],
+}
+
+impl<'a, 'b, Message, Renderer> overlay::Overlay<Message, Renderer>
+    for ToastsOverlay<'a, 'b, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn layout(&self, renderer: &Renderer, _bounds: Size, position: Point) -> layout::Node {
+        const PADDING: f32 = 16.0;
+
+        let limits = layout::Limits::new(Size::ZERO, self.size)
+            .width(Length::Fill)
+            .height(Length::Fill);
+        let node = layout::flex::resolve(
+            layout::flex::Axis::Vertical,
+            renderer,
+            &limits,
+            PADDING.into(),
+            8.0,
+            Alignment::Center,
+            self.toasts,
+        );
+
+        // Move the stack of notifications from the top to the bottom.
+        let stack_height = node
+            .children()
+            .last()
+            .map_or(0.0, |last| last.bounds().y + last.bounds().height + PADDING);
+        let offset = Vector::new(0.0, (self.size.height - stack_height).max(0.0));
+        let children = node
+            .children()
+            .iter()
+            .map(|child| child.clone().translate(offset))
+            .collect();
+
+        layout::Node::with_children(node.size(), children)
+            .translate(Vector::new(position.x, position.y))
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) {
+        let viewport = layout.bounds();
+        for ((toast, state), layout) in self
+            .toasts
+            .iter()
+            .zip(self.state.iter())
+            .zip(layout.children())
+        {
+            toast.as_widget().draw(
+                state,
+                renderer,
+                theme,
+                style,
+                layout,
+                cursor_position,
+                &viewport,
+            );
+        }
+    }
+
+    fn operate(
+        &mut self,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        for ((toast, state), layout) in self
+            .toasts
+            .iter()
+            .zip(self.state.iter_mut())
+            .zip(layout.children())
+        {
+            toast
+                .as_widget()
+                .operate(state, layout, renderer, operation);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        self.toasts
+            .iter_mut()
+            .zip(self.state.iter_mut())
+            .zip(layout.children())
+            .map(|((toast, state), layout)| {
+                toast.as_widget_mut().on_event(
+                    state,
+                    event.clone(),
+                    layout,
+                    cursor_position,
+                    renderer,
+                    clipboard,
+                    shell,
+                )
+            })
+            .fold(event::Status::Ignored, event::Status::merge)
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.toasts
+            .iter()
+            .zip(self.state.iter())
+            .zip(layout.children())
+            .map(|((toast, state), layout)| {
+                toast.as_widget().mouse_interaction(
+                    state,
+                    layout,
+                    cursor_position,
+                    viewport,
+                    renderer,
+                )
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn is_over(&self, layout: Layout<'_>, cursor_position: Point) -> bool {
+        layout
+            .children()
+            .any(|layout| layout.bounds().contains(cursor_position))
+    }
+}
+
+/// Content with a dialog shown over it, dimming the content and taking its events.
+pub struct Modal<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    dialog: Element<'a, Message, Renderer>,
+    on_dismiss: Option<Message>,
+}
+
+impl<'a, Message, Renderer> Modal<'a, Message, Renderer> {
+    /// Show a dialog, usually a container with a solid background, over some content.
+    pub fn new(
+        content: impl Into<Element<'a, Message, Renderer>>,
+        dialog: impl Into<Element<'a, Message, Renderer>>,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            dialog: dialog.into(),
+            on_dismiss: None,
+        }
+    }
+
+    /// Set the message produced when clicking outside of the dialog.
+    pub fn on_dismiss(mut self, message: Message) -> Self {
+        self.on_dismiss = Some(message);
+        self
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for Modal<'a, Message, Renderer>
+where
+    Message: Clone,
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content), Tree::new(&self.dialog)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(&[&self.content, &self.dialog]);
+    }
+
+    fn draw(
+        &self,
+        state: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &state.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+    }
+
+    fn operate(
+        &self,
+        state: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut state.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        state: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            &mut state.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &state.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        state: &'b mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        // Overlays of the content are hidden under the dialog.
+        Some(overlay::Element::new(
+            layout.position(),
+            Box::new(ModalOverlay {
+                size: layout.bounds().size(),
+                dialog: &mut self.dialog,
+                state: &mut state.children[1],
+                on_dismiss: self.on_dismiss.clone(),
+            }),
+        ))
+    }
+}
+
+impl<'a, Message, Renderer> From<Modal<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Message: Clone + 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(modal: Modal<'a, Message, Renderer>) -> Self {
+        Element::new(modal)
+    }
+}
+
+/// The dialog of a [`Modal`], centered over the content.
+struct ModalOverlay<'a, 'b, Message, Renderer> {
+    /// Size of the content.
+    size: Size,
+    dialog: &'b mut Element<'a, Message, Renderer>,
+    state: &'b mut Tree,
+    on_dismiss: Option<Message>,
+}
+
+impl<'a, 'b, Message, Renderer> overlay::Overlay<Message, Renderer>
+    for ModalOverlay<'a, 'b, Message, Renderer>
+where
+    Message: Clone,
+    Renderer: iced_native::Renderer,
+{
+    fn layout(&self, renderer: &Renderer, _bounds: Size, position: Point) -> layout::Node {
+        let limits = layout::Limits::new(Size::ZERO, self.size)
+            .width(Length::Fill)
+            .height(Length::Fill);
+        let dialog = self.dialog.as_widget().layout(renderer, &limits);
+        let offset = Vector::new(
+            (self.size.width - dialog.size().width).max(0.0) / 2.0,
+            (self.size.height - dialog.size().height).max(0.0) / 2.0,
+        );
+
+        layout::Node::with_children(self.size, vec![dialog.translate(offset)])
+            .translate(Vector::new(position.x, position.y))
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) {
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: layout.bounds(),
+                border_radius: 0.0.into(),
+                border_width: 0.0,
+                border_color: Color::TRANSPARENT,
+            },
+            Color {
+                a: 0.6,
+                ..Color::BLACK
+            },
+        );
+        if let Some(dialog_layout) = layout.children().next() {
+            self.dialog.as_widget().draw(
+                self.state,
+                renderer,
+                theme,
+                style,
+                dialog_layout,
+                cursor_position,
+                &layout.bounds(),
+            );
+        }
+    }
+
+    fn operate(
+        &mut self,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        if let Some(dialog_layout) = layout.children().next() {
+            self.dialog
+                .as_widget()
+                .operate(self.state, dialog_layout, renderer, operation);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let Some(dialog_layout) = layout.children().next() else {
+            return event::Status::Ignored;
+        };
+        if let (Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)), Some(message)) =
+            (&event, &self.on_dismiss)
+        {
+            if !dialog_layout.bounds().contains(cursor_position) {
+                shell.publish(message.clone());
+                return event::Status::Captured;
+            }
+        }
+
+        self.dialog.as_widget_mut().on_event(
+            self.state,
+            event,
+            dialog_layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        layout
+            .children()
+            .next()
+            .map(|dialog_layout| {
+                self.dialog.as_widget().mouse_interaction(
+                    self.state,
+                    dialog_layout,
+                    cursor_position,
+                    viewport,
+                    renderer,
+                )
+            })
+            .unwrap_or_default()
+    }
+}